@@ -0,0 +1,139 @@
+use core::hint;
+#[cfg(feature = "std")]
+use std::thread;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+/// Number of doublings of the spins of [`ExponentialBackoff`], the longest wait is 64 spins.
+const SPIN_LIMIT: u32 = 6;
+/// Number of doublings of the park duration of [`ParkBackoff`], the longest park is 1 ms.
+#[cfg(feature = "std")]
+const PARK_LIMIT: u32 = 10;
+
+///
+/// Strategy of a thread waiting for the lock of a [`crate::SpinLockObjectPool`].
+///
+/// A new value is created each time a thread has to wait and
+/// [`Backoff::snooze`] is called while the lock is held by another thread.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::{Backoff, PoolBuilder, SpinBackoff, SpinLockObjectPool};
+///  use std::thread;
+///
+///  // spin twice before yielding
+///  #[derive(Default)]
+///  struct Impatient(u32);
+///
+///  impl Backoff for Impatient {
+///    fn snooze(&mut self) {
+///      if self.0 < 2 {
+///        self.0 += 1;
+///        std::hint::spin_loop();
+///      } else {
+///        thread::yield_now();
+///      }
+///    }
+///  }
+///
+///  let pool: SpinLockObjectPool<u32, Impatient> = PoolBuilder::new(|| 0).build();
+///  let pool: SpinLockObjectPool<u32, SpinBackoff> = PoolBuilder::new(|| 0).build();
+/// ```
+pub trait Backoff: Default {
+    /// Wait before checking the lock again.
+    fn snooze(&mut self);
+}
+
+///
+/// Spin with a [`std::hint::spin_loop`] hint, for threads which have a dedicated core.
+#[derive(Default)]
+pub struct SpinBackoff;
+
+impl Backoff for SpinBackoff {
+    #[inline]
+    fn snooze(&mut self) {
+        hint::spin_loop();
+    }
+}
+
+///
+/// Spin twice as long at each wait, up to 64 [`std::hint::spin_loop`] hints,
+/// so that the waiting threads don't check the lock all at the same time.
+#[derive(Default)]
+pub struct ExponentialBackoff {
+    step: u32,
+}
+
+impl ExponentialBackoff {
+    /// Spin `2^step` times, return false without spinning once the longest spin is done.
+    #[inline]
+    fn spin(&mut self) -> bool {
+        if self.step > SPIN_LIMIT {
+            return false;
+        }
+        for _ in 0..1 << self.step {
+            hint::spin_loop();
+        }
+        self.step += 1;
+        true
+    }
+}
+
+impl Backoff for ExponentialBackoff {
+    #[inline]
+    fn snooze(&mut self) {
+        if !self.spin() {
+            for _ in 0..1 << SPIN_LIMIT {
+                hint::spin_loop();
+            }
+        }
+    }
+}
+
+///
+/// Spin like [`ExponentialBackoff`] then yield the time slice of the thread to
+/// the other ones. It's the default strategy, suitable when there are more
+/// threads than cores.
+///
+/// Without the `std` feature there is no scheduler to yield to, it keeps
+/// spinning like [`ExponentialBackoff`].
+#[derive(Default)]
+pub struct YieldBackoff {
+    spin: ExponentialBackoff,
+}
+
+impl Backoff for YieldBackoff {
+    #[inline]
+    fn snooze(&mut self) {
+        if !self.spin.spin() {
+            #[cfg(feature = "std")]
+            thread::yield_now();
+            #[cfg(not(feature = "std"))]
+            self.spin.snooze();
+        }
+    }
+}
+
+///
+/// Spin like [`ExponentialBackoff`] then park the thread, twice as long at each
+/// wait up to 1 ms, for oversubscribed machines where a lock holder may not be
+/// scheduled for a while.
+///
+/// Only available with the `std` feature.
+#[cfg(feature = "std")]
+#[derive(Default)]
+pub struct ParkBackoff {
+    spin: ExponentialBackoff,
+    step: u32,
+}
+
+#[cfg(feature = "std")]
+impl Backoff for ParkBackoff {
+    #[inline]
+    fn snooze(&mut self) {
+        if !self.spin.spin() {
+            thread::park_timeout(Duration::from_micros(1 << self.step));
+            self.step = (self.step + 1).min(PARK_LIMIT);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Count the objects of a pool against its `max_objects` limit.
+///
+/// An unbounded capacity never touches the counter.
+pub struct Capacity {
+    max: usize,
+    used: AtomicUsize,
+}
+
+impl Capacity {
+    #[inline]
+    pub fn new(max: usize) -> Self {
+        Self {
+            max,
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn unbounded() -> Self {
+        Self::new(usize::MAX)
+    }
+
+    #[inline]
+    pub fn is_bounded(&self) -> bool {
+        self.max != usize::MAX
+    }
+
+    #[inline]
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Reserve one object, return false if the limit is reached.
+    #[inline]
+    pub fn acquire(&self) -> bool {
+        !self.is_bounded()
+            || self
+                .used
+                .fetch_update(Ordering::Acquire, Ordering::Relaxed, |used| {
+                    if used < self.max {
+                        Some(used + 1)
+                    } else {
+                        None
+                    }
+                })
+                .is_ok()
+    }
+
+    /// Give back an object reserved with [`Capacity::acquire`].
+    #[inline]
+    pub fn release(&self) {
+        if self.is_bounded() {
+            let used = self.used.fetch_sub(1, Ordering::Release);
+            debug_assert!(used > 0, "released an object which wasn't acquired");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity_01() {
+        let capacity = Capacity::new(2);
+        assert!(capacity.acquire());
+        assert!(capacity.acquire());
+        assert!(!capacity.acquire());
+        capacity.release();
+        assert!(capacity.acquire());
+        assert!(!capacity.acquire());
+    }
+
+    #[test]
+    fn test_capacity_02() {
+        let capacity = Capacity::unbounded();
+        for _ in 0..100 {
+            assert!(capacity.acquire());
+        }
+        assert_eq!(capacity.used.load(Ordering::Relaxed), 0);
+    }
+}
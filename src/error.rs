@@ -0,0 +1,36 @@
+use alloc::boxed::Box;
+use core::error::Error;
+use core::fmt;
+
+/// Error returned by the `try_pull` functions of the object pools.
+#[derive(Debug)]
+pub enum PoolError {
+    /// The pool is bounded and all its objects are in use.
+    Exhausted,
+    /// No element has been returned to the pool before the timeout.
+    Timeout,
+    /// The `init` closure failed to create an element.
+    Init(Box<dyn Error + Send + Sync>),
+    /// No element passed the health check within the allowed retries.
+    Unhealthy,
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoolError::Exhausted => write!(f, "object pool exhausted"),
+            PoolError::Timeout => write!(f, "timeout while waiting for an object"),
+            PoolError::Init(err) => write!(f, "failed to create an object: {err}"),
+            PoolError::Unhealthy => write!(f, "no object passed the health check"),
+        }
+    }
+}
+
+impl Error for PoolError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PoolError::Init(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
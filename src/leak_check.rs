@@ -0,0 +1,163 @@
+use core::panic::Location;
+#[cfg(feature = "leak-check")]
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Id of the next tracked element, shared by all the pools so that the
+/// elements of several trackers are ordered by their pull.
+#[cfg(feature = "leak-check")]
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Element still checked out of a pool, returned by the `outstanding`
+/// functions of the object pools.
+///
+/// Only available with the `leak-check` feature.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::LinearObjectPool;
+///
+///  let pool = LinearObjectPool::<u32>::new(Default::default, |v| *v = 0);
+///  let item = pool.pull();
+///  std::mem::forget(item);
+///
+///  let outstanding = pool.outstanding();
+///  assert_eq!(outstanding.len(), 1);
+///  assert_eq!(outstanding[0].location.file(), file!());
+/// ```
+#[cfg(feature = "leak-check")]
+#[derive(Debug, Clone, Copy)]
+pub struct Outstanding {
+    /// Call site of the pull.
+    pub location: &'static Location<'static>,
+    /// Time elapsed since the pull.
+    pub age: Duration,
+    pub(crate) id: u64,
+}
+
+/// Identifier of an element checked out of a pool, it has no field without
+/// the `leak-check` feature.
+#[derive(Clone)]
+pub struct Ticket {
+    #[cfg(feature = "leak-check")]
+    id: u64,
+}
+
+/// Registry of the elements checked out of a pool, with their call site.
+///
+/// Without the `leak-check` feature it has no field and all its functions are no-ops.
+#[derive(Default)]
+pub struct LeakTracker {
+    #[cfg(feature = "leak-check")]
+    guards: Mutex<HashMap<u64, (&'static Location<'static>, Instant)>>,
+    #[cfg(feature = "leak-check")]
+    assert_on_drop: bool,
+}
+
+impl LeakTracker {
+    /// Tracker panicking when it's dropped with elements still checked out.
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    pub fn with_assert_on_drop(assert_on_drop: bool) -> Self {
+        Self {
+            guards: Mutex::new(HashMap::new()),
+            assert_on_drop,
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "leak-check"), allow(unused_variables))]
+    pub fn track(&self, location: &'static Location<'static>) -> Ticket {
+        #[cfg(feature = "leak-check")]
+        {
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            self.guards
+                .lock()
+                .unwrap()
+                .insert(id, (location, Instant::now()));
+            Ticket { id }
+        }
+        #[cfg(not(feature = "leak-check"))]
+        Ticket {}
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "leak-check"), allow(unused_variables))]
+    pub fn untrack(&self, ticket: &Ticket) {
+        #[cfg(feature = "leak-check")]
+        self.guards.lock().unwrap().remove(&ticket.id);
+    }
+
+    /// Elements checked out, the oldest first.
+    #[cfg(feature = "leak-check")]
+    pub fn outstanding(&self) -> Vec<Outstanding> {
+        let now = Instant::now();
+        let mut outstanding: Vec<_> = self
+            .guards
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, (location, pulled_at))| Outstanding {
+                location,
+                age: now.duration_since(*pulled_at),
+                id: *id,
+            })
+            .collect();
+        outstanding.sort_by_key(|item| item.id);
+        outstanding
+    }
+}
+
+#[cfg(feature = "leak-check")]
+impl Drop for LeakTracker {
+    fn drop(&mut self) {
+        if !self.assert_on_drop || thread::panicking() {
+            return;
+        }
+        let outstanding = self.outstanding();
+        if !outstanding.is_empty() {
+            let mut message = format!(
+                "object pool dropped with {} element(s) still checked out:",
+                outstanding.len()
+            );
+            for item in outstanding {
+                let _ = write!(
+                    message,
+                    "\n  pulled at {} {:?} ago",
+                    item.location, item.age
+                );
+            }
+            panic!("{message}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "leak-check"))]
+    #[test]
+    fn test_leak_tracker_disabled() {
+        assert_eq!(core::mem::size_of::<Ticket>(), 0);
+        assert_eq!(core::mem::size_of::<LeakTracker>(), 0);
+    }
+
+    #[cfg(feature = "leak-check")]
+    #[test]
+    fn test_leak_tracker_enabled() {
+        let tracker = LeakTracker::default();
+        let ticket1 = tracker.track(Location::caller());
+        let ticket2 = tracker.track(Location::caller());
+        assert_eq!(tracker.outstanding().len(), 2);
+        tracker.untrack(&ticket1);
+        tracker.untrack(&ticket2);
+        assert!(tracker.outstanding().is_empty());
+    }
+}
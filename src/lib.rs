@@ -3,12 +3,15 @@
 //! Some implementations are lockfree :
 //! * [`LinearObjectPool`]
 //! * [`SpinLockObjectPool`]
+//! * [`StackObjectPool`]
 //!
 //! Other use std::Mutex :
 //! * [`MutexObjectPool`]
 //!
 //! And [`NoneObjectPool`] basic allocation without pool.
 //!
+//! All of them implement the [`ObjectPool`] trait.
+//!
 //! ## Example
 //!
 //! The general pool creation looks like this for
@@ -24,6 +27,15 @@
 //!   *item = 5;
 //! ```
 //! At the end of the scope item return in object pool.
+//! ## `no_std`
+//!
+//! Without the default `std` feature, the crate is `no_std` and only needs `alloc`
+//! and 64-bit atomics.
+//! [`LinearObjectPool`], [`SpinLockObjectPool`], [`StackObjectPool`] and
+//! [`NoneObjectPool`] are still available, with `pull_owned` through
+//! `alloc::sync::Arc`. [`MutexObjectPool`], [`ShardedObjectPool`], [`ParkBackoff`],
+//! the thread cache of [`LinearObjectPool`], the blocking pulls and the `leak-check`
+//! feature need `std`.
 //! ## Multithreading
 //!
 //! All implementation support allocation/desallocation from on or more thread. You only need to wrap the pool in a [`std::sync::Arc`] :
@@ -80,29 +92,75 @@
 //!  [`crate 'object-pool'`]|20.517|44.798
 //!  
 //!  Report [monothreading](https://evaillant.github.io/lockfree-object-pool/benches/criterion/free/report/index.html) and [multithreading](https://evaillant.github.io/lockfree-object-pool/benches/criterion/multi%20thread%20free/report/index.html)
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod backoff;
+mod capacity;
+mod error;
+mod leak_check;
 mod linear_object_pool;
 mod linear_owned_reusable;
 mod linear_page;
 mod linear_reusable;
-mod mutex_object_pool;
-mod mutex_owned_reusable;
-mod mutex_reusable;
+mod locked_object_pool;
+mod locked_owned_reusable;
+mod locked_reusable;
+mod mapped_reusable;
+mod node_stack;
 mod none_object_pool;
 mod none_reusable;
+mod object_pool;
 mod page;
+mod pool_builder;
+mod pull_future;
+mod raw_lock;
+#[cfg(feature = "std")]
+mod sharded_object_pool;
+mod shared_reusable;
 mod spin_lock;
-mod spin_lock_object_pool;
-mod spin_lock_owned_reusable;
-mod spin_lock_reusable;
+mod stack_object_pool;
+mod stack_owned_reusable;
+mod stack_reusable;
+mod stats;
+#[cfg(feature = "std")]
+mod thread_cache;
+#[cfg(feature = "std")]
+mod thread_index;
+mod wait_queue;
 
+#[cfg(feature = "std")]
+pub use backoff::ParkBackoff;
+pub use backoff::{Backoff, ExponentialBackoff, SpinBackoff, YieldBackoff};
+pub use error::PoolError;
+#[cfg(feature = "leak-check")]
+pub use leak_check::Outstanding;
 pub use linear_object_pool::LinearObjectPool;
 pub use linear_owned_reusable::LinearOwnedReusable;
 pub use linear_reusable::LinearReusable;
-pub use mutex_object_pool::MutexObjectPool;
-pub use mutex_owned_reusable::MutexOwnedReusable;
-pub use mutex_reusable::MutexReusable;
+#[cfg(feature = "std")]
+pub use locked_object_pool::MutexObjectPool;
+pub use locked_object_pool::{LockedObjectPool, SpinLockObjectPool};
+#[cfg(feature = "std")]
+pub use locked_owned_reusable::MutexOwnedReusable;
+pub use locked_owned_reusable::{LockedOwnedReusable, SpinLockOwnedReusable};
+#[cfg(feature = "std")]
+pub use locked_reusable::MutexReusable;
+pub use locked_reusable::{LockedReusable, SpinLockReusable};
+pub use mapped_reusable::MappedReusable;
 pub use none_object_pool::NoneObjectPool;
 pub use none_reusable::NoneReusable;
-pub use spin_lock_object_pool::SpinLockObjectPool;
-pub use spin_lock_owned_reusable::SpinLockOwnedReusable;
-pub use spin_lock_reusable::SpinLockReusable;
+pub use object_pool::ObjectPool;
+pub use pool_builder::{Growth, PoolBuilder, ResetMode};
+pub use pull_future::{PullFuture, TryPullFuture};
+pub use raw_lock::RawLock;
+#[cfg(feature = "std")]
+pub use sharded_object_pool::{Shard, ShardedObjectPool};
+pub use shared_reusable::SharedReusable;
+pub use spin_lock::{SpinLock, SpinLockGuard};
+pub use stack_object_pool::StackObjectPool;
+pub use stack_owned_reusable::StackOwnedReusable;
+pub use stack_reusable::StackReusable;
+#[cfg(feature = "stats")]
+pub use stats::PoolStats;
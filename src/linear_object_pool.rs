@@ -1,16 +1,39 @@
+#[cfg(feature = "leak-check")]
+use crate::leak_check::Outstanding;
+#[cfg(feature = "stats")]
+use crate::stats::PoolStats;
 use crate::{
-    linear_owned_reusable::LinearOwnedReusable, linear_page::LinearPage,
+    capacity::Capacity,
+    error::PoolError,
+    leak_check::{LeakTracker, Ticket},
+    linear_owned_reusable::LinearOwnedReusable,
+    linear_page::LinearPage,
     linear_reusable::LinearReusable,
+    object_pool::ObjectPool,
+    page::{Page, PageId},
+    pool_builder::{PoolBuilder, ResetMode},
+    pull_future::{PullFuture, TryPullFuture, WaitablePool},
+    shared_reusable::SharedReusable,
+    stats::Stats,
+    wait_queue::WaitQueue,
 };
-use std::sync::Arc;
+#[cfg(feature = "std")]
+use crate::{thread_cache::ThreadCache, thread_index};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::error::Error;
+use core::panic::Location;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 /// ObjectPool use a lockfree vector to secure multithread access to pull.
 ///
-/// The lockfree vector is implemented as linked list.
+/// The lockfree vector is implemented as linked list of pages of `N` elements,
+/// from 1 to 64. Small pages suit huge elements, and big pages of tiny elements
+/// shorten the list. A pool of another width than 32 is built by [`PoolBuilder`].
 ///
 /// # Example
 /// ```rust
-///  use lockfree_object_pool::LinearObjectPool;
+///  use lockfree_object_pool::{LinearObjectPool, PoolBuilder};
 ///
 ///  let pool = LinearObjectPool::<u32>::new(
 ///    ||  Default::default(),
@@ -22,11 +45,31 @@ use std::sync::Arc;
 ///
 ///  *item = 5;
 ///  let work = *item * 5;
+///
+///  // pages of 8 elements
+///  let pool: LinearObjectPool<[u8; 4096], 8> = PoolBuilder::new(|| [0; 4096]).build();
 /// ```
-pub struct LinearObjectPool<T> {
+pub struct LinearObjectPool<T, const N: usize = 32> {
     reset: Box<dyn Fn(&mut T) + Send + Sync>,
-    init: Box<dyn Fn() -> T + Send + Sync>,
-    head: LinearPage<T>,
+    validate: Box<dyn Fn(&T) -> bool + Send + Sync>,
+    init: Box<dyn Fn() -> Result<T, PoolError> + Send + Sync>,
+    health_check: Box<dyn Fn(&T) -> bool + Send + Sync>,
+    health_check_retries: usize,
+    // declared before the pages it references, so that it's dropped first
+    #[cfg(feature = "std")]
+    cache: Box<ThreadCache<T, N>>,
+    head: Box<LinearPage<T, N>>,
+    capacity: Capacity,
+    /// Number of initialized elements, only counted by a bounded pool so that
+    /// `attach` doesn't exceed its `max_objects`.
+    live: AtomicUsize,
+    max_pages: usize,
+    grow_pages: usize,
+    striping: bool,
+    reset_mode: ResetMode,
+    waiters: WaitQueue,
+    stats: Stats,
+    tracker: LeakTracker,
 }
 
 impl<T> LinearObjectPool<T> {
@@ -52,15 +95,81 @@ impl<T> LinearObjectPool<T> {
     pub fn new<R, I>(init: I, reset: R) -> Self
     where
         R: Fn(&mut T) + 'static + Send + Sync,
-        I: Fn() -> T + 'static + Clone + Send + Sync,
+        I: Fn() -> T + 'static + Send + Sync,
     {
-        Self {
-            reset: Box::new(reset),
-            init: Box::new(init.clone()),
-            head: LinearPage::new(init),
-        }
+        PoolBuilder::new(init).reset(reset).build()
+    }
+
+    ///
+    /// Create an new [`LinearObjectPool`] with at most `max_objects` elements in use.
+    ///
+    /// When the limit is reached, [`LinearObjectPool::try_pull`] fails and
+    /// [`LinearObjectPool::pull`] panics.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    /// * `max_objects` maximum number of elements in use
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///
+    ///  let pool = LinearObjectPool::<u32>::new_bounded(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    },
+    ///    1
+    ///  );
+    ///  let item = pool.pull();
+    ///  assert!(pool.try_pull().is_err());
+    /// ```
+    #[inline]
+    pub fn new_bounded<R, I>(init: I, reset: R, max_objects: usize) -> Self
+    where
+        R: Fn(&mut T) + 'static + Send + Sync,
+        I: Fn() -> T + 'static + Send + Sync,
+    {
+        PoolBuilder::new(init)
+            .reset(reset)
+            .max_objects(max_objects)
+            .build()
     }
 
+    ///
+    /// Create an new [`LinearObjectPool`] whose elements creation can fail.
+    ///
+    /// The error of `init` is returned as [`PoolError::Init`] by the `try_pull` functions.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{LinearObjectPool, PoolError};
+    ///
+    ///  let pool = LinearObjectPool::<u32>::try_new(
+    ///    || "x".parse::<u32>(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ).unwrap();
+    ///  assert!(matches!(pool.try_pull(), Err(PoolError::Init(_))));
+    /// ```
+    #[inline]
+    pub fn try_new<R, I, E>(init: I, reset: R) -> Result<Self, PoolError>
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        PoolBuilder::try_new(init).reset(reset).try_build()
+    }
+}
+
+impl<T, const N: usize> LinearObjectPool<T, N> {
     ///
     /// Create a new element. When the element is dropped, it returns in the pull.
     ///
@@ -76,10 +185,37 @@ impl<T> LinearObjectPool<T> {
     ///  );
     ///  let mut item = pool.pull();
     /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are in use.
     #[inline]
-    pub fn pull(&self) -> LinearReusable<T> {
-        let (page, page_id) = self.head.alloc(&self.init);
-        unsafe { LinearReusable::new(self, page_id, page) }
+    #[track_caller]
+    pub fn pull(&self) -> LinearReusable<'_, T, N> {
+        self.try_pull().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///
+    ///  let pool = LinearObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let mut item = pool.try_pull().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull(&self) -> Result<LinearReusable<'_, T, N>, PoolError> {
+        let (page, page_id, hit) = self.alloc()?;
+        self.reuse(page, &page_id, hit)?;
+        let ticket = self.tracker.track(Location::caller());
+        Ok(unsafe { LinearReusable::new(self, page_id, page, ticket) })
     }
 
     ///
@@ -98,14 +234,567 @@ impl<T> LinearObjectPool<T> {
     ///  ));
     ///  let mut item = pool.pull_owned();
     /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are in use.
+    #[inline]
+    #[track_caller]
+    pub fn pull_owned(self: &Arc<Self>) -> LinearOwnedReusable<T, N> {
+        self.try_pull_owned().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(LinearObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let mut item = pool.try_pull_owned().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_owned(self: &Arc<Self>) -> Result<LinearOwnedReusable<T, N>, PoolError> {
+        self.try_pull_owned_at(Location::caller())
+    }
+
+    #[inline]
+    fn try_pull_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Result<LinearOwnedReusable<T, N>, PoolError> {
+        let (page, page_id, hit) = self.alloc()?;
+        self.reuse(page, &page_id, hit)?;
+        let ticket = self.tracker.track(location);
+        Ok(unsafe { LinearOwnedReusable::new(self.clone(), page_id, page, ticket) })
+    }
+
+    ///
+    /// Create a new element shared by reference counting. It returns in the pull
+    /// when the last clone is dropped.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(LinearObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let item = pool.pull_shared();
+    ///  let other = item.clone();
+    /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are in use.
+    #[inline]
+    #[track_caller]
+    pub fn pull_shared(self: &Arc<Self>) -> SharedReusable<T, N> {
+        self.try_pull_shared().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Create a new element shared by reference counting or fail if the pool is exhausted.
+    /// It returns in the pull when the last clone is dropped.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(LinearObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let item = pool.try_pull_shared().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_shared(self: &Arc<Self>) -> Result<SharedReusable<T, N>, PoolError> {
+        let (page, page_id, hit) = self.alloc()?;
+        self.reuse(page, &page_id, hit)?;
+        let ticket = self.tracker.track(Location::caller());
+        Ok(unsafe { SharedReusable::new(self.clone(), page_id, page, ticket) })
+    }
+
+    ///
+    /// Create a new element, waiting asynchronously for an element to be returned if the pool is exhausted.
+    /// When the element is dropped, it returns in the pull.
+    ///
+    /// The returned future doesn't depend on any executor, an unbounded pool never waits.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  async fn work(pool: Arc<LinearObjectPool<u32>>) {
+    ///    let mut item = pool.pull_async().await;
+    ///    *item = 5;
+    ///  }
+    /// ```
+    ///
+    /// # Panics
+    /// When polled, if the element can't be created or fails its health check.
+    #[inline]
+    #[track_caller]
+    pub fn pull_async(self: &Arc<Self>) -> PullFuture<T, Self> {
+        PullFuture::new(self.clone(), Location::caller())
+    }
+
+    ///
+    /// Same as `pull_async`, the future resolving to the error if the element
+    /// can't be created or fails its health check.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{LinearObjectPool, PoolError};
+    ///  use std::sync::Arc;
+    ///
+    ///  async fn work(pool: Arc<LinearObjectPool<u32>>) -> Result<(), PoolError> {
+    ///    let mut item = pool.try_pull_async().await?;
+    ///    *item = 5;
+    ///    Ok(())
+    ///  }
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_async(self: &Arc<Self>) -> TryPullFuture<T, Self> {
+        TryPullFuture::new(self.clone(), Location::caller())
+    }
+
+    ///
+    /// Give an element to the pool. It will be reset and reused by a next pull.
+    ///
+    /// The element takes a slot which holds no element, the pages are chained
+    /// as needed. If a bounded pool already holds `max_objects` elements, idle
+    /// or in use, `data` is dropped.
+    ///
+    /// # Arguments
+    /// * `data` element to add, it can have been created outside of the pool
+    ///   or detached from it
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///
+    ///  let pool = LinearObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  pool.attach(5);
+    ///  let item = pool.pull();
+    ///  assert_eq!(*item, 0);
+    /// ```
+    #[inline]
+    pub fn attach(&self, data: T) {
+        if !self.try_add_live() {
+            return;
+        }
+        if !self.capacity.acquire() {
+            self.remove_live();
+            return;
+        }
+        match self.head.alloc_uninit(self.max_pages, self.grow_pages) {
+            Some((page, page_id)) => unsafe {
+                // SAFETY: page_id has just been allocated and isn't initialized
+                page.write(&page_id, data);
+                self.store(page, &page_id);
+            },
+            None => {
+                self.remove_live();
+                self.release_capacity();
+            }
+        }
+    }
+
+    ///
+    /// Release the pages which are not needed to keep `min_objects` idle elements.
+    ///
+    /// As the pool is borrowed mutably, all its elements are idle. A shared pool
+    /// can be shrunk with [`Arc::get_mut`] once all its elements have returned.
+    ///
+    /// # Arguments
+    /// * `min_objects` number of elements to keep, rounded up to a whole page
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///
+    ///  let mut pool = LinearObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let items: Vec<_> = (0..100).map(|_| pool.pull()).collect();
+    ///  drop(items);
+    ///  pool.shrink_to(10);
+    /// ```
+    #[inline]
+    pub fn shrink_to(&mut self, min_objects: usize) {
+        #[cfg(feature = "std")]
+        self.cache.flush();
+        self.head
+            .truncate(min_objects.div_ceil(Page::<T, N>::CAPACITY).max(1));
+        *self.live.get_mut() = self.head.nb_init();
+    }
+
+    ///
+    /// Release all the pages except the first one.
+    ///
+    /// cf [`LinearObjectPool::shrink_to`]
+    #[inline]
+    pub fn trim(&mut self) {
+        self.shrink_to(0);
+    }
+
+    ///
+    /// Elements still checked out with the call site of their pull, the oldest first.
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    pub fn outstanding(&self) -> Vec<Outstanding> {
+        self.tracker.outstanding()
+    }
+
+    ///
+    /// Snapshot of the statistics of the pool.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> PoolStats {
+        let (pages, idle) = self.head.count();
+        #[cfg(feature = "std")]
+        let idle = idle + self.cache.len();
+        self.stats.snapshot(idle, pages)
+    }
+
+    /// Allocate an element, initialized on its first allocation, also return
+    /// false if `init` had to be called.
+    #[inline]
+    fn alloc(&self) -> Result<(&LinearPage<T, N>, PageId, bool), PoolError> {
+        let (page, page_id) = self.alloc_slot().ok_or(PoolError::Exhausted)?;
+        if page.is_init(&page_id) {
+            return Ok((page, page_id, true));
+        }
+        match self.call_init() {
+            Ok(data) => {
+                unsafe {
+                    // SAFETY: page_id has just been allocated so nobody else uses it
+                    page.write(&page_id, data);
+                }
+                self.add_live();
+                Ok((page, page_id, false))
+            }
+            Err(err) => {
+                self.vacate(page, &page_id);
+                Err(err)
+            }
+        }
+    }
+
+    /// Allocate a slot, which may not be initialized.
+    #[inline]
+    fn alloc_slot(&self) -> Option<(&LinearPage<T, N>, PageId)> {
+        if !self.capacity.acquire() {
+            return None;
+        }
+        #[cfg(feature = "std")]
+        if let Some(slot) = self.cache.pop() {
+            return Some(slot);
+        }
+        Some(
+            self.head
+                .alloc(self.stripe(), self.max_pages, self.grow_pages),
+        )
+    }
+
+    /// Offset of the first page searched by the current thread.
+    #[inline]
+    fn stripe(&self) -> usize {
+        if self.striping {
+            // the threads can only be told apart with std
+            #[cfg(feature = "std")]
+            if let Some(thread) = thread_index::current() {
+                return thread.index;
+            }
+        }
+        0
+    }
+
+    /// Prepare an element before its reusage, replacing it while it fails the
+    /// health check. The element is dropped and its slot freed on failure.
+    #[inline]
+    fn reuse(&self, page: &LinearPage<T, N>, page_id: &PageId, hit: bool) -> Result<(), PoolError> {
+        let data = unsafe {
+            // SAFETY: page_id has just been allocated so nobody else uses it
+            page.get_mut(page_id)
+        };
+        if self.reset_mode == ResetMode::OnPull {
+            (self.reset)(data);
+        }
+        let mut retries = self.health_check_retries;
+        while !(self.health_check)(data) {
+            if retries == 0 {
+                self.discard(page, page_id);
+                return Err(PoolError::Unhealthy);
+            }
+            retries -= 1;
+            match self.call_init() {
+                Ok(new) => *data = new,
+                Err(err) => {
+                    self.discard(page, page_id);
+                    return Err(err);
+                }
+            }
+        }
+        self.stats.on_pull(hit);
+        Ok(())
+    }
+
+    /// Return an element in the pool.
+    ///
+    /// # Safety
+    /// * `page_id` has to be allocated in `page` and not used anymore
+    #[inline]
+    pub(crate) unsafe fn recycle(
+        &self,
+        page: &LinearPage<T, N>,
+        page_id: &PageId,
+        ticket: &Ticket,
+    ) {
+        self.tracker.untrack(ticket);
+        self.stats.on_return();
+        self.store(page, page_id);
+    }
+
+    /// # Safety
+    /// * `page_id` has to be allocated in `page` and not used anymore
+    #[inline]
+    unsafe fn store(&self, page: &LinearPage<T, N>, page_id: &PageId) {
+        let data = page.get_mut(page_id);
+        if !(self.validate)(data) {
+            self.discard(page, page_id);
+            return;
+        }
+        if self.reset_mode == ResetMode::OnReturn {
+            (self.reset)(data);
+        }
+        self.release(page, page_id);
+    }
+
+    /// Take a detached element out of the pool, its slot is initialized again
+    /// by its next allocation.
+    ///
+    /// # Safety
+    /// * `page_id` has to be allocated in `page` and not used anymore
+    #[inline]
+    pub(crate) unsafe fn on_detach(
+        &self,
+        page: &LinearPage<T, N>,
+        page_id: &PageId,
+        ticket: &Ticket,
+    ) -> T {
+        self.tracker.untrack(ticket);
+        self.stats.on_detach();
+        let data = page.take(page_id);
+        self.remove_live();
+        self.vacate(page, page_id);
+        data
+    }
+
+    /// Drop an element and free its slot.
+    #[inline]
+    fn discard(&self, page: &LinearPage<T, N>, page_id: &PageId) {
+        // the slot is initialized again by its next allocation
+        drop(unsafe {
+            // SAFETY: page_id is allocated and initialized, nobody else uses it
+            page.take(page_id)
+        });
+        self.remove_live();
+        self.vacate(page, page_id);
+    }
+
+    /// Count an element initialized by a pull.
     #[inline]
-    pub fn pull_owned(self: &Arc<Self>) -> LinearOwnedReusable<T> {
-        let (page, page_id) = self.head.alloc(&self.init);
-        unsafe { LinearOwnedReusable::new(self.clone(), page_id, page) }
+    fn add_live(&self) {
+        if self.capacity.is_bounded() {
+            self.live.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Count an attached element, return false if the pool is full.
+    #[inline]
+    fn try_add_live(&self) -> bool {
+        !self.capacity.is_bounded()
+            || self
+                .live
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |live| {
+                    (live < self.capacity.max()).then_some(live + 1)
+                })
+                .is_ok()
+    }
+
+    #[inline]
+    fn remove_live(&self) {
+        if self.capacity.is_bounded() {
+            self.live.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Free an uninitialized slot, it never goes in the thread cache.
+    #[inline]
+    fn vacate(&self, page: &LinearPage<T, N>, page_id: &PageId) {
+        page.free(page_id);
+        self.release_capacity();
     }
 
     #[inline]
-    pub(crate) fn get_reset_callback(&self) -> &dyn Fn(&mut T) {
-        &self.reset
+    fn release(&self, page: &LinearPage<T, N>, page_id: &PageId) {
+        #[cfg(feature = "std")]
+        if self.cache.push(page, page_id) {
+            self.release_capacity();
+            return;
+        }
+        page.free(page_id);
+        self.release_capacity();
+    }
+
+    #[inline]
+    fn release_capacity(&self) {
+        if self.capacity.is_bounded() {
+            self.capacity.release();
+            self.waiters.notify_one();
+        }
+    }
+
+    #[inline]
+    fn call_init(&self) -> Result<T, PoolError> {
+        self.stats.on_init();
+        (self.init)()
+    }
+}
+
+impl<T, const N: usize> ObjectPool<T> for LinearObjectPool<T, N> {
+    type Reusable<'a>
+        = LinearReusable<'a, T, N>
+    where
+        Self: 'a;
+    type OwnedReusable = LinearOwnedReusable<T, N>;
+
+    #[inline]
+    #[track_caller]
+    fn pull(&self) -> Self::Reusable<'_> {
+        LinearObjectPool::pull(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn pull_owned(self: &Arc<Self>) -> Self::OwnedReusable {
+        LinearObjectPool::pull_owned(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn try_pull(&self) -> Result<Self::Reusable<'_>, PoolError> {
+        LinearObjectPool::try_pull(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn try_pull_owned(self: &Arc<Self>) -> Result<Self::OwnedReusable, PoolError> {
+        LinearObjectPool::try_pull_owned(self)
+    }
+
+    #[cfg(feature = "stats")]
+    #[inline]
+    fn stats(&self) -> PoolStats {
+        LinearObjectPool::stats(self)
+    }
+
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    fn outstanding(&self) -> Vec<Outstanding> {
+        LinearObjectPool::outstanding(self)
+    }
+}
+
+impl<T, const N: usize> WaitablePool<T> for LinearObjectPool<T, N> {
+    #[inline]
+    fn waiters(&self) -> &WaitQueue {
+        &self.waiters
+    }
+
+    #[inline]
+    fn try_pull_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Result<Self::OwnedReusable, PoolError> {
+        LinearObjectPool::try_pull_owned_at(self, location)
+    }
+}
+
+impl<T, const N: usize> TryFrom<PoolBuilder<T>> for LinearObjectPool<T, N> {
+    type Error = PoolError;
+
+    #[inline]
+    fn try_from(builder: PoolBuilder<T>) -> Result<Self, Self::Error> {
+        let capacity = builder.capacity();
+        let max_pages = if capacity.is_bounded() {
+            capacity.max().div_ceil(Page::<T, N>::CAPACITY).max(1)
+        } else {
+            usize::MAX
+        };
+        let stats = Stats::default();
+        let tracker = builder.leak_tracker();
+        let init = || {
+            stats.on_init();
+            (builder.init)()
+        };
+        // a bounded pool expects an idle element in the pages for each available capacity
+        #[cfg(feature = "std")]
+        let cache = ThreadCache::new(if capacity.is_bounded() {
+            0
+        } else {
+            builder.thread_cache
+        });
+        let head = LinearPage::new();
+        unsafe {
+            // SAFETY: the pool isn't built yet so nothing is allocated
+            head.prewarm(init, builder.prewarm_count())?;
+        }
+        let live = AtomicUsize::new(head.nb_init());
+        Ok(Self {
+            reset: builder.reset,
+            validate: builder.validate,
+            init: builder.init,
+            health_check: builder.health_check,
+            health_check_retries: builder.health_check_retries,
+            #[cfg(feature = "std")]
+            cache,
+            head,
+            capacity,
+            live,
+            max_pages,
+            grow_pages: builder.growth.batch_size().div_ceil(Page::<T, N>::CAPACITY),
+            striping: builder.striping,
+            reset_mode: builder.reset_mode,
+            waiters: WaitQueue::new(),
+            stats,
+            tracker,
+        })
     }
 }
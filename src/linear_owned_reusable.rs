@@ -1,7 +1,13 @@
+use crate::leak_check::Ticket;
 use crate::linear_object_pool::LinearObjectPool;
-use crate::page::{Page, PageId};
-use std::ops::{Deref, DerefMut};
-use std::sync::Arc;
+use crate::linear_page::LinearPage;
+use crate::mapped_reusable::MappedReusable;
+use crate::page::PageId;
+use crate::shared_reusable::SharedReusable;
+use alloc::sync::Arc;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+use core::ptr;
 
 /// Wrapper over T used by [`LinearObjectPool`].
 ///
@@ -22,37 +28,134 @@ use std::sync::Arc;
 ///  *item = 5;
 ///  let work = *item * 5;
 /// ```
-pub struct LinearOwnedReusable<T> {
-    pool: Arc<LinearObjectPool<T>>,
+pub struct LinearOwnedReusable<T, const N: usize = 32> {
+    pool: Arc<LinearObjectPool<T, N>>,
     page_id: PageId,
-    page: *const Page<T>,
+    page: *const LinearPage<T, N>,
+    ticket: Ticket,
 }
 
-impl<T> LinearOwnedReusable<T> {
+impl<T, const N: usize> LinearOwnedReusable<T, N> {
     /// Create new element
     ///
     /// # Arguments
     /// * `pool` object pool owner
     /// * `page_id` page id
     /// * `page`    page that contains data
+    /// * `ticket`  leak check ticket of the pull
     /// # Safety
     /// * `page` has to be a valid pointer to a page in `pool`
     /// * `pool_id` has to be a valid id for `page`
     #[inline]
     pub(crate) unsafe fn new(
-        pool: Arc<LinearObjectPool<T>>,
+        pool: Arc<LinearObjectPool<T, N>>,
         page_id: PageId,
-        page: &Page<T>,
+        page: &LinearPage<T, N>,
+        ticket: Ticket,
     ) -> Self {
         Self {
             pool,
             page_id,
             page,
+            ticket,
         }
     }
+
+    /// Take the element out of the pool. It will never return in the pool.
+    ///
+    /// The slot of the page is filled with a new element by its next pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(LinearObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let mut item = pool.pull_owned();
+    ///  *item = 5;
+    ///  let data: u32 = item.detach();
+    ///  assert_eq!(data, 5);
+    /// ```
+    #[inline]
+    pub fn detach(self) -> T {
+        let this = ManuallyDrop::new(self);
+        let pool = unsafe {
+            // SAFETY: this is never dropped so pool is moved out once
+            ptr::read(&this.pool)
+        };
+        unsafe {
+            // SAFETY: there exists only this `LinearOwnedReusable` with this page_id
+            pool.on_detach(this.page.as_ref().unwrap(), &this.page_id, &this.ticket)
+        }
+    }
+
+    /// Share the element between several owners, it returns in the pool when
+    /// the last [`SharedReusable`] is dropped.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(LinearObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let mut item = pool.pull_owned();
+    ///  *item = 5;
+    ///  let item = item.share();
+    ///  let other = item.clone();
+    ///  assert_eq!(*other, 5);
+    /// ```
+    #[inline]
+    pub fn share(self) -> SharedReusable<T, N> {
+        let this = ManuallyDrop::new(self);
+        unsafe {
+            // SAFETY: this is never dropped so each field is moved out once
+            let pool = ptr::read(&this.pool);
+            let ticket = ptr::read(&this.ticket);
+            // SAFETY: there exists only this `LinearOwnedReusable` with this page_id
+            SharedReusable::new(pool, this.page_id, this.page.as_ref().unwrap(), ticket)
+        }
+    }
+
+    /// Make a guard of a part of the element, like `std::cell::RefMut::map`.
+    ///
+    /// The whole element returns in the pool when the new guard is dropped. This
+    /// is an associated function to not shadow the functions of the element.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{LinearObjectPool, LinearOwnedReusable};
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(LinearObjectPool::<(u32, String)>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      v.1.clear();
+    ///    }
+    ///  ));
+    ///  let mut item = LinearOwnedReusable::map(pool.pull_owned(), |v| &mut v.1);
+    ///  item.push_str("hello");
+    ///  assert_eq!(item.len(), 5);
+    /// ```
+    #[inline]
+    pub fn map<U, F>(this: Self, f: F) -> MappedReusable<Self, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedReusable::new(this, f)
+    }
 }
 
-impl<T> DerefMut for LinearOwnedReusable<T> {
+impl<T, const N: usize> DerefMut for LinearOwnedReusable<T, N> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         unsafe {
@@ -62,7 +165,7 @@ impl<T> DerefMut for LinearOwnedReusable<T> {
     }
 }
 
-impl<T> Deref for LinearOwnedReusable<T> {
+impl<T, const N: usize> Deref for LinearOwnedReusable<T, N> {
     type Target = T;
 
     #[inline]
@@ -74,17 +177,16 @@ impl<T> Deref for LinearOwnedReusable<T> {
     }
 }
 
-impl<T> Drop for LinearOwnedReusable<T> {
+impl<T, const N: usize> Drop for LinearOwnedReusable<T, N> {
     #[inline]
     fn drop(&mut self) {
         unsafe {
             // SAFETY: there exists only this `LinearOwnedReusable` with this page_id
-            let page = self.page.as_ref().unwrap();
-            (self.pool.get_reset_callback())(page.get_mut(&self.page_id));
-            page.free(&self.page_id);
+            self.pool
+                .recycle(self.page.as_ref().unwrap(), &self.page_id, &self.ticket);
         }
     }
 }
 
-unsafe impl<T: Send> Send for LinearOwnedReusable<T> {} // SAFETY: sending the data is allowed if it's Send
-unsafe impl<T: Send> Sync for LinearOwnedReusable<T> {} // SAFETY: the Mutex manages synchronization so only Send is required
+unsafe impl<T: Send, const N: usize> Send for LinearOwnedReusable<T, N> {} // SAFETY: sending the data is allowed if it's Send
+unsafe impl<T: Send, const N: usize> Sync for LinearOwnedReusable<T, N> {} // SAFETY: the Mutex manages synchronization so only Send is required
@@ -1,37 +1,70 @@
 use crate::page::{Page, PageId};
-use std::ptr;
-use std::sync::atomic::{AtomicPtr, Ordering};
+use alloc::boxed::Box;
+use core::hint;
+use core::mem;
+use core::ops::Deref;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
 
-pub struct LinearPage<T> {
-    page: Page<T>,
-    next: AtomicPtr<LinearPage<T>>,
+/// Page of a [`crate::LinearObjectPool`] chained to the next one.
+///
+/// The head of the chain keeps the first page which may have a free element,
+/// all the previous ones being full, so that an allocation skips them, and
+/// the number of pages, so that the threads can start from different pages.
+pub struct LinearPage<T, const N: usize> {
+    page: Page<T, N>,
+    next: AtomicPtr<LinearPage<T, N>>,
+    /// Position in the chain, 0 for the head.
+    index: usize,
+    head: *const LinearPage<T, N>,
+    /// First page which may have a free element, only used by the head.
+    first_free: AtomicPtr<LinearPage<T, N>>,
+    /// Number of pages of the chain, only used by the head.
+    nb_pages: AtomicUsize,
 }
 
-impl<T> LinearPage<T> {
+impl<T, const N: usize> LinearPage<T, N> {
+    /// Create the head of a chain, it's boxed so that the other pages can reference it.
     #[inline]
-    pub fn new<I>(init: I) -> Self
-    where
-        I: Fn() -> T,
-    {
+    pub fn new() -> Box<Self> {
+        let mut head = Box::new(Self::with_head(ptr::null(), 0));
+        head.head = &*head;
+        *head.first_free.get_mut() = &mut *head;
+        head
+    }
+
+    #[inline]
+    fn with_head(head: *const Self, index: usize) -> Self {
         Self {
-            page: Page::new(init),
+            page: Page::new(),
             next: AtomicPtr::new(ptr::null_mut()),
+            index,
+            head,
+            first_free: AtomicPtr::new(ptr::null_mut()),
+            nb_pages: AtomicUsize::new(1),
         }
     }
 
+    /// Return the next page, creating a chain of `nb_pages` pages if there is none.
     #[inline]
-    pub fn get_or_create_next<I>(&self, init: I) -> &Self
-    where
-        I: Fn() -> T,
-    {
+    pub fn get_or_create_next(&self, nb_pages: usize) -> &Self {
         let mut current = self.next.load(Ordering::Relaxed);
         if current.is_null() {
-            let new = Box::into_raw(Box::new(LinearPage::<T>::new(init)));
+            let nb_pages = nb_pages.max(1);
+            let mut new: *mut Self = ptr::null_mut();
+            for offset in (1..=nb_pages).rev() {
+                let page = LinearPage::<T, N>::with_head(self.head, self.index + offset);
+                page.next.store(new, Ordering::Relaxed);
+                new = Box::into_raw(Box::new(page));
+            }
             match self
                 .next
                 .compare_exchange(current, new, Ordering::SeqCst, Ordering::Relaxed)
             {
                 Ok(_) => {
+                    self.head()
+                        .nb_pages
+                        .fetch_max(self.index + nb_pages + 1, Ordering::SeqCst);
                     current = new;
                 }
                 Err(x) => {
@@ -49,34 +82,332 @@ impl<T> LinearPage<T> {
         }
     }
 
+    /// Initialize `count` elements, chaining the pages to hold them.
+    ///
+    /// # Safety
+    /// * no element of the chain has to be allocated
     #[inline]
-    pub fn alloc<I>(&self, init: I) -> (&Page<T>, PageId)
+    pub unsafe fn prewarm<I, E>(&self, init: I, mut count: usize) -> Result<(), E>
     where
-        I: Fn() -> T + Clone,
+        I: Fn() -> Result<T, E>,
     {
         let mut linear_page = self;
+        loop {
+            count -= linear_page.page.fill(&init, count)?;
+            if count == 0 {
+                return Ok(());
+            }
+            linear_page = linear_page.get_or_create_next(1);
+        }
+    }
+
+    /// Return the number of pages of the chain and of their idle elements.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn count(&self) -> (usize, usize) {
+        let mut pages = 0;
+        let mut idle = 0;
+        let mut current: *const Self = self;
+        while let Some(linear_page) = unsafe {
+            // SAFETY: the pages are only dropped with the pool
+            current.as_ref()
+        } {
+            pages += 1;
+            idle += linear_page.page.nb_idle();
+            current = linear_page.next.load(Ordering::Acquire);
+        }
+        (pages, idle)
+    }
+
+    /// Return the number of initialized elements of the chain.
+    #[inline]
+    pub fn nb_init(&self) -> usize {
+        let mut nb_init = 0;
+        let mut current = Some(self);
+        while let Some(linear_page) = current {
+            nb_init += linear_page.page.nb_init();
+            current = linear_page.next();
+        }
+        nb_init
+    }
+
+    /// Drop the pages after the `nb_pages` first ones.
+    #[inline]
+    pub fn truncate(&mut self, nb_pages: usize) {
+        // the first free page may be dropped, the search restarts from the head
+        let head: *mut Self = self;
+        *self.first_free.get_mut() = head;
+        let len = self.nb_pages.get_mut();
+        *len = (*len).min(nb_pages.max(1));
+        let mut linear_page = self;
+        for _ in 1..nb_pages {
+            let next = *linear_page.next.get_mut();
+            if next.is_null() {
+                return;
+            }
+            linear_page = unsafe {
+                // SAFETY: next was allocated with Box::new and self is borrowed mutably
+                &mut *next
+            };
+        }
+        let next = mem::replace(linear_page.next.get_mut(), ptr::null_mut());
+        if !next.is_null() {
+            unsafe {
+                // SAFETY: next was allocated with Box::new
+                drop(Box::from_raw(next))
+            };
+        }
+    }
+
+    /// Allocate an element, at most `max_pages` pages are chained and they
+    /// are added `grow_pages` at a time.
+    ///
+    /// The search starts from the first page which may have a free element,
+    /// offset by `stripe` pages wrapping around the chain, then it goes on from
+    /// the first page which may have a free element. When the last allowed
+    /// page is full, it restarts from `self`, so the caller has to ensure that
+    /// a free element exists. The element may not be initialized.
+    #[inline]
+    pub fn alloc(&self, stripe: usize, max_pages: usize, grow_pages: usize) -> (&Self, PageId) {
+        let mut linear_page = self.first_free();
+        if stripe > 0 {
+            if let Some(found) = self.alloc_stripe(linear_page, stripe) {
+                return found;
+            }
+        }
         loop {
             match linear_page.page.alloc() {
                 Some(id) => {
-                    return (&linear_page.page, id);
+                    return (linear_page, id);
+                }
+                None if linear_page.index + 1 < max_pages => {
+                    let nb_pages = linear_page.index + 1;
+                    let next = linear_page.get_or_create_next(grow_pages.min(max_pages - nb_pages));
+                    self.skip(linear_page, next);
+                    linear_page = next;
                 }
                 None => {
-                    linear_page = linear_page.get_or_create_next(init.clone());
+                    hint::spin_loop();
+                    linear_page = self;
                 }
             };
         }
     }
+
+    /// Allocate an element which isn't initialized, at most `max_pages` pages
+    /// are chained and they are added `grow_pages` at a time.
+    ///
+    /// Return none if all the elements of the allowed pages are initialized or
+    /// in use.
+    #[inline]
+    pub fn alloc_uninit(&self, max_pages: usize, grow_pages: usize) -> Option<(&Self, PageId)> {
+        let mut linear_page = self.first_free();
+        loop {
+            while let Some(id) = linear_page.page.alloc_uninit() {
+                if !linear_page.page.is_init(&id) {
+                    return Some((linear_page, id));
+                }
+                // the element has been initialized since the bitmap was read
+                linear_page.free(&id);
+            }
+            if linear_page.index + 1 >= max_pages {
+                return None;
+            }
+            let nb_pages = linear_page.index + 1;
+            linear_page = linear_page.get_or_create_next(grow_pages.min(max_pages - nb_pages));
+        }
+    }
+
+    /// Allocate an element in the existing pages, starting `stripe` pages
+    /// after `first`, wrapping around the pages after `first`.
+    #[inline]
+    fn alloc_stripe<'a>(&self, first: &'a Self, stripe: usize) -> Option<(&'a Self, PageId)> {
+        let nb_pages = self
+            .nb_pages
+            .load(Ordering::SeqCst)
+            .saturating_sub(first.index)
+            .max(1);
+        let mut linear_page = first;
+        for _ in 0..stripe % nb_pages {
+            linear_page = linear_page.next()?;
+        }
+        loop {
+            if let Some(id) = linear_page.page.alloc() {
+                return Some((linear_page, id));
+            }
+            // the pages from first to the stripe are searched by alloc
+            linear_page = linear_page.next()?;
+        }
+    }
+
+    /// Free an element, the page is advertised to the allocations if it was full.
+    #[inline]
+    pub fn free(&self, id: &PageId) {
+        if self.page.free(id) {
+            self.head().advertise(self);
+        }
+    }
+
+    #[inline]
+    fn head(&self) -> &Self {
+        unsafe {
+            // SAFETY: the head lives as long as the chain
+            &*self.head
+        }
+    }
+
+    #[inline]
+    fn next(&self) -> Option<&Self> {
+        unsafe {
+            // SAFETY: the next pages are only dropped with the head
+            self.next.load(Ordering::Acquire).as_ref()
+        }
+    }
+
+    #[inline]
+    fn first_free(&self) -> &Self {
+        unsafe {
+            // SAFETY: first_free is self or a page of the chain
+            &*self.first_free.load(Ordering::SeqCst)
+        }
+    }
+
+    /// Move the first free page after `full`, if it's still the first free page.
+    #[inline]
+    fn skip(&self, full: &Self, next: &Self) {
+        let full_ptr = full as *const Self as *mut Self;
+        let next_ptr = next as *const Self as *mut Self;
+        if self
+            .first_free
+            .compare_exchange(full_ptr, next_ptr, Ordering::SeqCst, Ordering::Relaxed)
+            .is_ok()
+            && full.page.has_free()
+        {
+            // an element has been freed before the move without moving back the first free page
+            self.advertise(full);
+        }
+    }
+
+    /// Move the first free page back to `page` if it's before.
+    #[inline]
+    fn advertise(&self, page: &Self) {
+        let page_ptr = page as *const Self as *mut Self;
+        let mut current = self.first_free.load(Ordering::SeqCst);
+        while page.index
+            < unsafe {
+                // SAFETY: first_free is self or a page of the chain
+                (*current).index
+            }
+        {
+            match self.first_free.compare_exchange(
+                current,
+                page_ptr,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => return,
+                Err(x) => current = x,
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for LinearPage<T, N> {
+    type Target = Page<T, N>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.page
+    }
 }
 
-impl<T> Drop for LinearPage<T> {
+impl<T, const N: usize> Drop for LinearPage<T, N> {
     #[inline]
     fn drop(&mut self) {
-        let current = self.next.load(Ordering::Relaxed);
-        if !current.is_null() {
-            unsafe {
+        // the chain is dropped page by page, a recursive drop could overflow the stack
+        let mut current = mem::replace(self.next.get_mut(), ptr::null_mut());
+        while !current.is_null() {
+            let mut page = unsafe {
                 // SAFETY: current was allocated with Box::new
-                drop(Box::from_raw(current))
+                Box::from_raw(current)
             };
+            current = mem::replace(page.next.get_mut(), ptr::null_mut());
         }
     }
 }
+
+unsafe impl<T: Send, const N: usize> Send for LinearPage<T, N> {} // SAFETY: head is only used to reach the chain
+unsafe impl<T: Sync, const N: usize> Sync for LinearPage<T, N> {} // SAFETY: head is only used to reach the chain
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_page_first_free() {
+        let head = LinearPage::<u32, 8>::new();
+        let ids: Vec<_> = (0..24).map(|_| head.alloc(0, usize::MAX, 1)).collect();
+        assert_eq!(ids.last().map(|(page, _)| page.index), Some(2));
+        // the full pages are skipped
+        assert_eq!(head.first_free().index, 2);
+
+        let (page, id) = ids[3];
+        page.free(&id);
+        assert_eq!(head.first_free().index, 0);
+        let (reused, reused_id) = head.alloc(0, usize::MAX, 1);
+        assert!(ptr::eq(reused, page));
+        assert_eq!(reused_id, id);
+        assert_eq!(head.alloc(0, usize::MAX, 1).0.index, 3);
+        assert_eq!(head.first_free().index, 3);
+    }
+
+    #[test]
+    fn test_linear_page_stripe() {
+        let head = LinearPage::<u32, 8>::new();
+        head.get_or_create_next(3);
+        assert_eq!(head.nb_pages.load(Ordering::Relaxed), 4);
+        assert_eq!(head.alloc(2, usize::MAX, 1).0.index, 2);
+        // the stripe wraps around the chain
+        assert_eq!(head.alloc(5, usize::MAX, 1).0.index, 1);
+        assert_eq!(head.alloc(0, usize::MAX, 1).0.index, 0);
+
+        // the search goes on from the first page when the following ones are full
+        let ids: Vec<_> = (0..15).map(|_| head.alloc(3, usize::MAX, 1)).collect();
+        assert_eq!(ids.last().map(|(page, _)| page.index), Some(0));
+        assert_eq!(head.alloc(3, usize::MAX, 1).0.index, 1);
+    }
+
+    #[test]
+    fn test_linear_page_alloc_uninit() {
+        let head = LinearPage::<u32, 8>::new();
+        let (page, id) = head.alloc(0, usize::MAX, 1);
+        unsafe { page.write(&id, 1) };
+        page.free(&id);
+
+        // the initialized element is skipped
+        let (page, id) = head.alloc_uninit(usize::MAX, 1).unwrap();
+        assert_eq!((page.index, id), (0, 1));
+        for _ in 0..6 {
+            head.alloc_uninit(usize::MAX, 1).unwrap();
+        }
+        // a page is chained once the first one only holds initialized elements
+        assert_eq!(head.alloc_uninit(usize::MAX, 1).unwrap().0.index, 1);
+        assert!(head.alloc_uninit(2, 1).is_some());
+
+        let head = LinearPage::<u32, 8>::new();
+        for id in 0..8 {
+            unsafe { head.write(&id, id as u32) };
+        }
+        assert!(head.alloc_uninit(1, 1).is_none());
+    }
+
+    #[test]
+    fn test_linear_page_truncate() {
+        let mut head = LinearPage::<u32, 8>::new();
+        let ids: Vec<_> = (0..24).map(|_| head.alloc(0, usize::MAX, 1).1).collect();
+        drop(ids);
+        head.truncate(1);
+        assert_eq!(head.first_free().index, 0);
+        assert_eq!(head.nb_pages.load(Ordering::Relaxed), 1);
+    }
+}
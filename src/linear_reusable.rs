@@ -1,6 +1,10 @@
+use crate::leak_check::Ticket;
 use crate::linear_object_pool::LinearObjectPool;
-use crate::page::{Page, PageId};
-use std::ops::{Deref, DerefMut};
+use crate::linear_page::LinearPage;
+use crate::mapped_reusable::MappedReusable;
+use crate::page::PageId;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
 
 /// Wrapper over T used by [`LinearObjectPool`].
 ///
@@ -20,37 +24,96 @@ use std::ops::{Deref, DerefMut};
 ///  *item = 5;
 ///  let work = *item * 5;
 /// ```
-pub struct LinearReusable<'a, T> {
-    pool: &'a LinearObjectPool<T>,
+pub struct LinearReusable<'a, T, const N: usize = 32> {
+    pool: &'a LinearObjectPool<T, N>,
     page_id: PageId,
-    page: &'a Page<T>,
+    page: &'a LinearPage<T, N>,
+    ticket: Ticket,
 }
 
-impl<'a, T> LinearReusable<'a, T> {
+impl<'a, T, const N: usize> LinearReusable<'a, T, N> {
     /// Create new element
     ///
     /// # Arguments
     /// * `pool` object pool owner
     /// * `page_id` page id
     /// * `page`    page that contains data
+    /// * `ticket`  leak check ticket of the pull
     /// # Safety
     /// * `page` has to be a valid pointer to a page in `pool`
     /// * `pool_id` has to be a valid id for `page`
     #[inline]
     pub(crate) unsafe fn new(
-        pool: &'a LinearObjectPool<T>,
+        pool: &'a LinearObjectPool<T, N>,
         page_id: PageId,
-        page: &'a Page<T>,
+        page: &'a LinearPage<T, N>,
+        ticket: Ticket,
     ) -> Self {
         Self {
             pool,
             page_id,
             page,
+            ticket,
         }
     }
+
+    /// Take the element out of the pool. It will never return in the pool.
+    ///
+    /// The slot of the page is filled with a new element by its next pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::LinearObjectPool;
+    ///
+    ///  let pool = LinearObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let mut item = pool.pull();
+    ///  *item = 5;
+    ///  let data: u32 = item.detach();
+    ///  assert_eq!(data, 5);
+    /// ```
+    #[inline]
+    pub fn detach(self) -> T {
+        let this = ManuallyDrop::new(self);
+        unsafe {
+            // SAFETY: there exists only this `LinearReusable` with this page_id and it isn't dropped
+            this.pool.on_detach(this.page, &this.page_id, &this.ticket)
+        }
+    }
+
+    /// Make a guard of a part of the element, like `std::cell::RefMut::map`.
+    ///
+    /// The whole element returns in the pool when the new guard is dropped. This
+    /// is an associated function to not shadow the functions of the element.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{LinearObjectPool, LinearReusable};
+    ///
+    ///  let pool = LinearObjectPool::<(u32, String)>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      v.1.clear();
+    ///    }
+    ///  );
+    ///  let mut item = LinearReusable::map(pool.pull(), |v| &mut v.1);
+    ///  item.push_str("hello");
+    ///  assert_eq!(item.len(), 5);
+    /// ```
+    #[inline]
+    pub fn map<U, F>(this: Self, f: F) -> MappedReusable<Self, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedReusable::new(this, f)
+    }
 }
 
-impl<T> DerefMut for LinearReusable<'_, T> {
+impl<T, const N: usize> DerefMut for LinearReusable<'_, T, N> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         unsafe {
@@ -60,7 +123,7 @@ impl<T> DerefMut for LinearReusable<'_, T> {
     }
 }
 
-impl<T> Deref for LinearReusable<'_, T> {
+impl<T, const N: usize> Deref for LinearReusable<'_, T, N> {
     type Target = T;
 
     #[inline]
@@ -72,14 +135,12 @@ impl<T> Deref for LinearReusable<'_, T> {
     }
 }
 
-impl<T> Drop for LinearReusable<'_, T> {
+impl<T, const N: usize> Drop for LinearReusable<'_, T, N> {
     #[inline]
     fn drop(&mut self) {
-        let page = self.page;
-        (self.pool.get_reset_callback())(unsafe {
+        unsafe {
             // SAFETY: there exists only this `LinearReusable` with this page_id
-            page.get_mut(&self.page_id)
-        });
-        page.free(&self.page_id);
+            self.pool.recycle(self.page, &self.page_id, &self.ticket);
+        }
     }
 }
@@ -0,0 +1,1001 @@
+#[cfg(feature = "leak-check")]
+use crate::leak_check::Outstanding;
+#[cfg(feature = "stats")]
+use crate::stats::PoolStats;
+use crate::{
+    backoff::{Backoff, YieldBackoff},
+    capacity::Capacity,
+    error::PoolError,
+    leak_check::{LeakTracker, Ticket},
+    locked_owned_reusable::LockedOwnedReusable,
+    locked_reusable::LockedReusable,
+    object_pool::ObjectPool,
+    pool_builder::{Growth, PoolBuilder, ResetMode},
+    pull_future::{PullFuture, TryPullFuture, WaitablePool},
+    raw_lock::RawLock,
+    spin_lock::SpinLock,
+    stats::Stats,
+    wait_queue::WaitQueue,
+};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::mem::ManuallyDrop;
+use core::panic::Location;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// ObjectPool use a [`std::sync::Mutex`] over vector to secure multithread access to pull.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::MutexObjectPool;
+///
+///  let pool = MutexObjectPool::<u32>::new(
+///    ||  Default::default(),
+///    |v| {
+///      *v = 0;
+///    }
+///  );
+///  let mut item = pool.pull();
+///
+///  *item = 5;
+///  let work = *item * 5;
+/// ```
+#[cfg(feature = "std")]
+pub type MutexObjectPool<T> = LockedObjectPool<T, Mutex<Vec<T>>>;
+
+/// ObjectPool use a spin lock over vector to secure multithread access to pull.
+///
+/// The spin lock works like [`std::sync::Mutex`] but
+/// * use [`std::sync::atomic::AtomicBool`] for synchro
+/// * active waiting, tuned by the [`crate::Backoff`] strategy `B`
+///
+/// cf [wikipedia](https://en.wikipedia.org/wiki/Spinlock) for more information.
+///
+/// The lock is unfair by default, [`PoolBuilder::fair_lock`] makes it a ticket
+/// lock granted in the order of the requests.
+///
+/// The default [`YieldBackoff`] suits machines with more threads than cores,
+/// [`crate::SpinBackoff`] suits threads pinned to dedicated cores and
+/// [`crate::ParkBackoff`] suits oversubscribed containers.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::{PoolBuilder, SpinBackoff, SpinLockObjectPool};
+///
+///  let pool = SpinLockObjectPool::<u32>::new(
+///    ||  Default::default(),
+///    |v| {
+///      *v = 0;
+///    }
+///  );
+///  let mut item = pool.pull();
+///
+///  *item = 5;
+///  let work = *item * 5;
+///
+///  // spin without yielding
+///  let pool: SpinLockObjectPool<u32, SpinBackoff> = PoolBuilder::new(|| 0).build();
+/// ```
+pub type SpinLockObjectPool<T, B = YieldBackoff> = LockedObjectPool<T, SpinLock<Vec<T>, B>>;
+
+/// ObjectPool use a lock `L` over vector to secure multithread access to pull.
+///
+/// [`MutexObjectPool`] and [`SpinLockObjectPool`] use the locks of the crate,
+/// any other lock implementing [`RawLock`] can be used and the pool is then
+/// built by a [`PoolBuilder`].
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::{LockedObjectPool, PoolBuilder, SpinLock};
+///
+///  let pool: LockedObjectPool<u32, SpinLock<Vec<u32>>> = PoolBuilder::new(|| 0).build();
+///  let mut item = pool.pull();
+///
+///  *item = 5;
+///  let work = *item * 5;
+/// ```
+pub struct LockedObjectPool<T, L: RawLock<Vec<T>>> {
+    objects: L,
+    reset: Box<dyn Fn(&mut T) + Send + Sync>,
+    validate: Box<dyn Fn(&T) -> bool + Send + Sync>,
+    init: Box<dyn Fn() -> Result<T, PoolError> + Send + Sync>,
+    health_check: Box<dyn Fn(&T) -> bool + Send + Sync>,
+    health_check_retries: usize,
+    capacity: Capacity,
+    reset_mode: ResetMode,
+    growth: Growth,
+    max_idle: usize,
+    /// Shared with the other shards of a sharded pool.
+    waiters: Arc<WaitQueue>,
+    stats: Stats,
+    tracker: LeakTracker,
+}
+
+#[cfg(feature = "std")]
+impl<T> LockedObjectPool<T, Mutex<Vec<T>>> {
+    ///
+    /// Create an new [`MutexObjectPool`]
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::MutexObjectPool;
+    ///
+    ///  let pool = MutexObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    /// ```
+    #[inline]
+    pub fn new<R, I>(init: I, reset: R) -> Self
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> T + Send + Sync + 'static,
+    {
+        PoolBuilder::new(init).reset(reset).build()
+    }
+
+    ///
+    /// Create an new [`MutexObjectPool`] with at most `max_objects` elements.
+    ///
+    /// When the limit is reached, [`MutexObjectPool::try_pull`] fails and
+    /// [`MutexObjectPool::pull`] panics.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    /// * `max_objects` maximum number of elements
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::MutexObjectPool;
+    ///
+    ///  let pool = MutexObjectPool::<u32>::new_bounded(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    },
+    ///    1
+    ///  );
+    ///  let item = pool.pull();
+    ///  assert!(pool.try_pull().is_err());
+    /// ```
+    #[inline]
+    pub fn new_bounded<R, I>(init: I, reset: R, max_objects: usize) -> Self
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> T + Send + Sync + 'static,
+    {
+        PoolBuilder::new(init)
+            .reset(reset)
+            .max_objects(max_objects)
+            .build()
+    }
+
+    ///
+    /// Create an new [`MutexObjectPool`] whose elements creation can fail.
+    ///
+    /// The error of `init` is returned as [`PoolError::Init`] by the `try_pull` functions.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{MutexObjectPool, PoolError};
+    ///
+    ///  let pool = MutexObjectPool::<u32>::try_new(
+    ///    || "x".parse::<u32>(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ).unwrap();
+    ///  assert!(matches!(pool.try_pull(), Err(PoolError::Init(_))));
+    /// ```
+    #[inline]
+    pub fn try_new<R, I, E>(init: I, reset: R) -> Result<Self, PoolError>
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        PoolBuilder::try_new(init).reset(reset).try_build()
+    }
+}
+
+impl<T, B: Backoff> LockedObjectPool<T, SpinLock<Vec<T>, B>> {
+    ///
+    /// Create an new [`SpinLockObjectPool`]
+    ///
+    /// The backoff of the lock is [`YieldBackoff`] unless the type of the pool is written.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{SpinBackoff, SpinLockObjectPool};
+    ///
+    ///  let pool = SpinLockObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let pool = SpinLockObjectPool::<u32, SpinBackoff>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    /// ```
+    #[inline]
+    pub fn new<R, I>(init: I, reset: R) -> Self
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> T + Send + Sync + 'static,
+    {
+        PoolBuilder::new(init).reset(reset).build()
+    }
+
+    ///
+    /// Create an new [`SpinLockObjectPool`] with at most `max_objects` elements.
+    ///
+    /// When the limit is reached, [`SpinLockObjectPool::try_pull`] fails and
+    /// [`SpinLockObjectPool::pull`] panics.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    /// * `max_objects` maximum number of elements
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///
+    ///  let pool = SpinLockObjectPool::<u32>::new_bounded(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    },
+    ///    1
+    ///  );
+    ///  let item = pool.pull();
+    ///  assert!(pool.try_pull().is_err());
+    /// ```
+    #[inline]
+    pub fn new_bounded<R, I>(init: I, reset: R, max_objects: usize) -> Self
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> T + Send + Sync + 'static,
+    {
+        PoolBuilder::new(init)
+            .reset(reset)
+            .max_objects(max_objects)
+            .build()
+    }
+
+    ///
+    /// Create an new [`SpinLockObjectPool`] whose elements creation can fail.
+    ///
+    /// The error of `init` is returned as [`PoolError::Init`] by the `try_pull` functions.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{SpinLockObjectPool, PoolError};
+    ///
+    ///  let pool = SpinLockObjectPool::<u32>::try_new(
+    ///    || "x".parse::<u32>(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ).unwrap();
+    ///  assert!(matches!(pool.try_pull(), Err(PoolError::Init(_))));
+    /// ```
+    #[inline]
+    pub fn try_new<R, I, E>(init: I, reset: R) -> Result<Self, PoolError>
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        PoolBuilder::try_new(init).reset(reset).try_build()
+    }
+}
+
+impl<T, L: RawLock<Vec<T>>> LockedObjectPool<T, L> {
+    ///
+    /// Create a new element. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///
+    ///  let pool = SpinLockObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let mut item = pool.pull();
+    /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are in use.
+    #[inline]
+    #[track_caller]
+    pub fn pull(&self) -> LockedReusable<'_, T, L> {
+        self.try_pull().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///
+    ///  let pool = SpinLockObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let mut item = pool.try_pull().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull(&self) -> Result<LockedReusable<'_, T, L>, PoolError> {
+        Ok(LockedReusable::from_pool(
+            self,
+            ManuallyDrop::new(self.take()?),
+        ))
+    }
+
+    ///
+    /// Create a new element. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(SpinLockObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let mut item = pool.pull_owned();
+    /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are in use.
+    #[inline]
+    #[track_caller]
+    pub fn pull_owned(self: &Arc<Self>) -> LockedOwnedReusable<T, L> {
+        self.try_pull_owned().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(SpinLockObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let mut item = pool.try_pull_owned().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_owned(self: &Arc<Self>) -> Result<LockedOwnedReusable<T, L>, PoolError> {
+        self.try_pull_owned_at(Location::caller())
+    }
+
+    #[inline]
+    fn try_pull_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Result<LockedOwnedReusable<T, L>, PoolError> {
+        let data = ManuallyDrop::new(self.take()?);
+        Ok(LockedOwnedReusable::with_ticket(
+            self.clone(),
+            data,
+            self.tracker.track(location),
+        ))
+    }
+
+    /// Pull an idle element without calling `init`, none if there isn't any.
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    pub(crate) fn try_pull_idle(&self) -> Option<Result<LockedReusable<'_, T, L>, PoolError>> {
+        match self.take_idle()? {
+            Ok(data) => Some(Ok(LockedReusable::from_pool(self, ManuallyDrop::new(data)))),
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    /// Pull an idle element without calling `init`, none if there isn't any.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn try_pull_idle_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Option<Result<LockedOwnedReusable<T, L>, PoolError>> {
+        match self.take_idle()? {
+            Ok(data) => Some(Ok(LockedOwnedReusable::with_ticket(
+                self.clone(),
+                ManuallyDrop::new(data),
+                self.tracker.track(location),
+            ))),
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    ///
+    /// Create a new element, waiting for an element to be returned if the pool is exhausted.
+    /// When the element is dropped, it returns in the pull.
+    ///
+    /// The wait is done by parking the thread, an unbounded pool never waits.
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///
+    ///  let pool = SpinLockObjectPool::<u32>::new_bounded(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    },
+    ///    1
+    ///  );
+    ///  let mut item = pool.pull_blocking();
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    pub fn pull_blocking(&self) -> LockedReusable<'_, T, L> {
+        let data = self.wait(None).unwrap_or_else(|err| panic!("{err}"));
+        LockedReusable::from_pool(self, ManuallyDrop::new(data))
+    }
+
+    ///
+    /// Create a new element, waiting for an element to be returned if the pool is exhausted.
+    /// When the element is dropped, it returns in the pull.
+    ///
+    /// The wait is done by parking the thread, an unbounded pool never waits.
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(SpinLockObjectPool::<u32>::new_bounded(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    },
+    ///    1
+    ///  ));
+    ///  let mut item = pool.pull_owned_blocking();
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    pub fn pull_owned_blocking(self: &Arc<Self>) -> LockedOwnedReusable<T, L> {
+        let data = self.wait(None).unwrap_or_else(|err| panic!("{err}"));
+        LockedOwnedReusable::from_pool(self.clone(), ManuallyDrop::new(data))
+    }
+
+    ///
+    /// Create a new element, waiting at most `timeout` for an element to be returned if the pool is exhausted.
+    /// When the element is dropped, it returns in the pull.
+    ///
+    /// # Arguments
+    /// * `timeout` maximum duration of the wait
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{SpinLockObjectPool, PoolError};
+    ///  use std::time::Duration;
+    ///
+    ///  let pool = SpinLockObjectPool::<u32>::new_bounded(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    },
+    ///    1
+    ///  );
+    ///  let item = pool.pull_timeout(Duration::from_millis(10)).unwrap();
+    ///  assert!(matches!(
+    ///    pool.pull_timeout(Duration::from_millis(10)),
+    ///    Err(PoolError::Timeout)
+    ///  ));
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    pub fn pull_timeout(&self, timeout: Duration) -> Result<LockedReusable<'_, T, L>, PoolError> {
+        let data = self.wait(Instant::now().checked_add(timeout))?;
+        Ok(LockedReusable::from_pool(self, ManuallyDrop::new(data)))
+    }
+
+    ///
+    /// Create a new element, waiting at most `timeout` for an element to be returned if the pool is exhausted.
+    /// When the element is dropped, it returns in the pull.
+    ///
+    /// # Arguments
+    /// * `timeout` maximum duration of the wait
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///  use std::sync::Arc;
+    ///  use std::time::Duration;
+    ///
+    ///  let pool = Arc::new(SpinLockObjectPool::<u32>::new_bounded(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    },
+    ///    1
+    ///  ));
+    ///  let item = pool.pull_owned_timeout(Duration::from_millis(10)).unwrap();
+    ///  assert!(pool.pull_owned_timeout(Duration::from_millis(10)).is_err());
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    pub fn pull_owned_timeout(
+        self: &Arc<Self>,
+        timeout: Duration,
+    ) -> Result<LockedOwnedReusable<T, L>, PoolError> {
+        let data = self.wait(Instant::now().checked_add(timeout))?;
+        Ok(LockedOwnedReusable::from_pool(
+            self.clone(),
+            ManuallyDrop::new(data),
+        ))
+    }
+
+    ///
+    /// Create a new element, waiting asynchronously for an element to be returned if the pool is exhausted.
+    /// When the element is dropped, it returns in the pull.
+    ///
+    /// The returned future doesn't depend on any executor, an unbounded pool never waits.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  async fn work(pool: Arc<SpinLockObjectPool<u32>>) {
+    ///    let mut item = pool.pull_async().await;
+    ///    *item = 5;
+    ///  }
+    /// ```
+    ///
+    /// # Panics
+    /// When polled, if the element can't be created or fails its health check.
+    #[inline]
+    #[track_caller]
+    pub fn pull_async(self: &Arc<Self>) -> PullFuture<T, Self> {
+        PullFuture::new(self.clone(), Location::caller())
+    }
+
+    ///
+    /// Same as `pull_async`, the future resolving to the error if the element
+    /// can't be created or fails its health check.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{SpinLockObjectPool, PoolError};
+    ///  use std::sync::Arc;
+    ///
+    ///  async fn work(pool: Arc<SpinLockObjectPool<u32>>) -> Result<(), PoolError> {
+    ///    let mut item = pool.try_pull_async().await?;
+    ///    *item = 5;
+    ///    Ok(())
+    ///  }
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_async(self: &Arc<Self>) -> TryPullFuture<T, Self> {
+        TryPullFuture::new(self.clone(), Location::caller())
+    }
+
+    ///
+    /// Give an element to the pool. It will be reset and reused by a next pull.
+    ///
+    /// # Arguments
+    /// * `data` element to add, it can have been created outside of the pool
+    ///   or detached from it. If the pool is bounded and full, it is dropped.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///
+    ///  let pool = SpinLockObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  pool.attach(5);
+    ///  let item = pool.pull();
+    ///  assert_eq!(*item, 0);
+    /// ```
+    #[inline]
+    pub fn attach(&self, data: T) {
+        if self.capacity.acquire() {
+            self.store(data);
+        }
+    }
+
+    ///
+    /// Drop the idle elements above `min_idle` and release the unused memory of the idle list.
+    ///
+    /// # Arguments
+    /// * `min_idle` number of idle elements to keep
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///
+    ///  let pool = SpinLockObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let items: Vec<_> = (0..100).map(|_| pool.pull()).collect();
+    ///  drop(items);
+    ///  pool.shrink_to(10);
+    /// ```
+    #[inline]
+    pub fn shrink_to(&self, min_idle: usize) {
+        let surplus = {
+            let mut objects = self.objects.lock();
+            let surplus = if objects.len() > min_idle {
+                objects.split_off(min_idle)
+            } else {
+                Vec::new()
+            };
+            objects.shrink_to(min_idle);
+            surplus
+        };
+        let count = surplus.len();
+        drop(surplus);
+        for _ in 0..count {
+            self.release();
+        }
+    }
+
+    ///
+    /// Drop all the idle elements.
+    ///
+    /// cf [`LockedObjectPool::shrink_to`]
+    #[inline]
+    pub fn trim(&self) {
+        self.shrink_to(0);
+    }
+
+    ///
+    /// Elements still checked out with the call site of their pull, the oldest first.
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    pub fn outstanding(&self) -> Vec<Outstanding> {
+        self.tracker.outstanding()
+    }
+
+    ///
+    /// Snapshot of the statistics of the pool, `pages` is always 0.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> PoolStats {
+        let idle = self.objects.lock().len();
+        self.stats.snapshot(idle, 0)
+    }
+
+    #[inline]
+    fn take(&self) -> Result<T, PoolError> {
+        match self.take_idle() {
+            Some(data) => data,
+            None if self.capacity.acquire() => self.create(),
+            None => Err(PoolError::Exhausted),
+        }
+    }
+
+    /// Take an idle element, none if there isn't any.
+    #[inline]
+    fn take_idle(&self) -> Option<Result<T, PoolError>> {
+        let data = self.objects.lock().pop()?;
+        Some(self.reuse(data))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn wait(&self, deadline: Option<Instant>) -> Result<T, PoolError> {
+        loop {
+            let waiter = {
+                let mut objects = self.objects.lock();
+                if let Some(data) = objects.pop() {
+                    drop(objects);
+                    return self.reuse(data);
+                }
+                if self.capacity.acquire() {
+                    drop(objects);
+                    return self.create();
+                }
+                self.waiters.register_thread()
+            };
+            // on timeout, a notification received meanwhile is consumed by a last try
+            if !waiter.park(deadline) && self.waiters.cancel(&waiter) {
+                return Err(PoolError::Timeout);
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn recycle(&self, data: T, ticket: &Ticket) {
+        self.tracker.untrack(ticket);
+        self.stats.on_return();
+        self.store(data);
+    }
+
+    #[inline]
+    fn store(&self, mut data: T) {
+        if !(self.validate)(&data) {
+            drop(data);
+            self.release();
+            return;
+        }
+        if self.reset_mode == ResetMode::OnReturn {
+            (self.reset)(&mut data);
+        }
+        self.push(data);
+    }
+
+    #[inline]
+    fn push(&self, data: T) {
+        let mut objects = self.objects.lock();
+        if objects.len() >= self.max_idle {
+            drop(objects);
+            drop(data);
+            self.release();
+            return;
+        }
+        objects.push(data);
+        if self.capacity.is_bounded() {
+            self.waiters.notify_one();
+        }
+    }
+
+    /// Prepare an idle element before its reusage, its capacity is released on failure.
+    #[inline]
+    fn reuse(&self, mut data: T) -> Result<T, PoolError> {
+        if self.reset_mode == ResetMode::OnPull {
+            (self.reset)(&mut data);
+        }
+        self.check(data).inspect(|_| self.stats.on_pull(true))
+    }
+
+    /// Create an element, its capacity has already been acquired and is released on failure.
+    #[inline]
+    fn create(&self) -> Result<T, PoolError> {
+        match self
+            .grow(self.growth.batch_size() - 1)
+            .and_then(|_| self.call_init())
+        {
+            Ok(data) => self.check(data).inspect(|_| self.stats.on_pull(false)),
+            Err(err) => {
+                self.release();
+                Err(err)
+            }
+        }
+    }
+
+    /// Replace an element failing the health check by new ones, its capacity is released on failure.
+    #[inline]
+    fn check(&self, mut data: T) -> Result<T, PoolError> {
+        let mut retries = self.health_check_retries;
+        while !(self.health_check)(&data) {
+            if retries == 0 {
+                drop(data);
+                self.release();
+                return Err(PoolError::Unhealthy);
+            }
+            retries -= 1;
+            data = match self.call_init() {
+                Ok(data) => data,
+                Err(err) => {
+                    self.release();
+                    return Err(err);
+                }
+            };
+        }
+        Ok(data)
+    }
+
+    #[inline]
+    pub(crate) fn track(&self, location: &'static Location<'static>) -> Ticket {
+        self.tracker.track(location)
+    }
+
+    #[inline]
+    fn call_init(&self) -> Result<T, PoolError> {
+        self.stats.on_init();
+        (self.init)()
+    }
+
+    /// Add at most `count` idle elements, stopping at the first failure.
+    #[inline]
+    fn grow(&self, count: usize) -> Result<(), PoolError> {
+        for _ in 0..count {
+            if !self.capacity.acquire() {
+                break;
+            }
+            match self.call_init() {
+                Ok(data) => self.push(data),
+                Err(err) => {
+                    self.release();
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn on_detach(&self, ticket: &Ticket) {
+        self.tracker.untrack(ticket);
+        self.stats.on_detach();
+        self.release();
+    }
+
+    /// Count an element created outside of the pool as pulled.
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are in use.
+    #[inline]
+    pub(crate) fn adopt(&self) {
+        if !self.capacity.acquire() {
+            panic!("{}", PoolError::Exhausted);
+        }
+        self.stats.on_pull(false);
+    }
+
+    /// Release the capacity of a dropped element.
+    #[inline]
+    fn release(&self) {
+        self.capacity.release();
+        if self.capacity.is_bounded() {
+            let _objects = self.objects.lock();
+            self.waiters.notify_one();
+        }
+    }
+}
+
+impl<T, L: RawLock<Vec<T>>> ObjectPool<T> for LockedObjectPool<T, L> {
+    type Reusable<'a>
+        = LockedReusable<'a, T, L>
+    where
+        Self: 'a;
+    type OwnedReusable = LockedOwnedReusable<T, L>;
+
+    #[inline]
+    #[track_caller]
+    fn pull(&self) -> Self::Reusable<'_> {
+        LockedObjectPool::pull(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn pull_owned(self: &Arc<Self>) -> Self::OwnedReusable {
+        LockedObjectPool::pull_owned(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn try_pull(&self) -> Result<Self::Reusable<'_>, PoolError> {
+        LockedObjectPool::try_pull(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn try_pull_owned(self: &Arc<Self>) -> Result<Self::OwnedReusable, PoolError> {
+        LockedObjectPool::try_pull_owned(self)
+    }
+
+    #[cfg(feature = "stats")]
+    #[inline]
+    fn stats(&self) -> PoolStats {
+        LockedObjectPool::stats(self)
+    }
+
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    fn outstanding(&self) -> Vec<Outstanding> {
+        LockedObjectPool::outstanding(self)
+    }
+}
+
+impl<T, L: RawLock<Vec<T>>> WaitablePool<T> for LockedObjectPool<T, L> {
+    #[inline]
+    fn waiters(&self) -> &WaitQueue {
+        &self.waiters
+    }
+
+    #[inline]
+    fn try_pull_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Result<Self::OwnedReusable, PoolError> {
+        LockedObjectPool::try_pull_owned_at(self, location)
+    }
+}
+
+impl<T, L: RawLock<Vec<T>>> TryFrom<PoolBuilder<T>> for LockedObjectPool<T, L> {
+    type Error = PoolError;
+
+    #[inline]
+    fn try_from(builder: PoolBuilder<T>) -> Result<Self, Self::Error> {
+        let prewarm = builder.prewarm_count();
+        let capacity = builder.capacity();
+        let tracker = builder.leak_tracker();
+        let pool = Self {
+            objects: if builder.fair_lock {
+                L::new_fair(Vec::new())
+            } else {
+                L::new(Vec::new())
+            },
+            reset: builder.reset,
+            validate: builder.validate,
+            init: builder.init,
+            health_check: builder.health_check,
+            health_check_retries: builder.health_check_retries,
+            capacity,
+            reset_mode: builder.reset_mode,
+            growth: builder.growth,
+            max_idle: builder.max_idle.unwrap_or(usize::MAX),
+            waiters: builder
+                .waiters
+                .unwrap_or_else(|| Arc::new(WaitQueue::new())),
+            stats: Stats::default(),
+            tracker,
+        };
+        pool.grow(prewarm)?;
+        Ok(pool)
+    }
+}
@@ -0,0 +1,155 @@
+use crate::backoff::YieldBackoff;
+use crate::leak_check::Ticket;
+use crate::locked_object_pool::LockedObjectPool;
+use crate::mapped_reusable::MappedReusable;
+use crate::raw_lock::RawLock;
+use crate::spin_lock::SpinLock;
+use alloc::vec::Vec;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+use core::panic::Location;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+/// Wrapper over T used by [`crate::MutexObjectPool`].
+#[cfg(feature = "std")]
+pub type MutexReusable<'a, T> = LockedReusable<'a, T, Mutex<Vec<T>>>;
+
+/// Wrapper over T used by [`crate::SpinLockObjectPool`].
+pub type SpinLockReusable<'a, T, B = YieldBackoff> = LockedReusable<'a, T, SpinLock<Vec<T>, B>>;
+
+/// Wrapper over T used by [`LockedObjectPool`].
+///
+/// Access is allowed with [`std::ops::Deref`] or [`std::ops::DerefMut`]
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::SpinLockObjectPool;
+///
+///  let pool = SpinLockObjectPool::<u32>::new(
+///    ||  Default::default(),
+///    |v| {
+///      *v = 0;
+///    }
+///  );
+///  let mut item = pool.pull();
+///
+///  *item = 5;
+///  let work = *item * 5;
+/// ```
+pub struct LockedReusable<'a, T, L: RawLock<Vec<T>>> {
+    pool: &'a LockedObjectPool<T, L>,
+    data: ManuallyDrop<T>,
+    ticket: Ticket,
+}
+
+impl<'a, T, L: RawLock<Vec<T>>> LockedReusable<'a, T, L> {
+    /// Create new element
+    ///
+    /// The element counts as pulled from the pool, it returns in the pool when
+    /// it's dropped.
+    ///
+    /// # Arguments
+    /// * `pool` object pool owner
+    /// * `data` element to wrappe
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are in use.
+    #[inline]
+    #[track_caller]
+    pub fn new(pool: &'a LockedObjectPool<T, L>, data: ManuallyDrop<T>) -> Self {
+        pool.adopt();
+        Self::from_pool(pool, data)
+    }
+
+    /// Wrap an element pulled from `pool`.
+    #[inline]
+    #[track_caller]
+    pub(crate) fn from_pool(pool: &'a LockedObjectPool<T, L>, data: ManuallyDrop<T>) -> Self {
+        Self {
+            pool,
+            data,
+            ticket: pool.track(Location::caller()),
+        }
+    }
+
+    /// Take the element out of the pool. It will never return in the pool.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::SpinLockObjectPool;
+    ///
+    ///  let pool = SpinLockObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let mut item = pool.pull();
+    ///  *item = 5;
+    ///  let data: u32 = item.detach();
+    ///  assert_eq!(data, 5);
+    /// ```
+    #[inline]
+    pub fn detach(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        this.pool.on_detach(&this.ticket);
+        unsafe {
+            // SAFETY: this.data is never referenced again and this isn't dropped
+            ManuallyDrop::take(&mut this.data)
+        }
+    }
+
+    /// Make a guard of a part of the element, like `std::cell::RefMut::map`.
+    ///
+    /// The whole element returns in the pool when the new guard is dropped. This
+    /// is an associated function to not shadow the functions of the element.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{SpinLockObjectPool, SpinLockReusable};
+    ///
+    ///  let pool = SpinLockObjectPool::<(u32, String)>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      v.1.clear();
+    ///    }
+    ///  );
+    ///  let mut item = SpinLockReusable::map(pool.pull(), |v| &mut v.1);
+    ///  item.push_str("hello");
+    ///  assert_eq!(item.len(), 5);
+    /// ```
+    #[inline]
+    pub fn map<U, F>(this: Self, f: F) -> MappedReusable<Self, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedReusable::new(this, f)
+    }
+}
+
+impl<T, L: RawLock<Vec<T>>> DerefMut for LockedReusable<'_, T, L> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl<T, L: RawLock<Vec<T>>> Deref for LockedReusable<'_, T, L> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<T, L: RawLock<Vec<T>>> Drop for LockedReusable<'_, T, L> {
+    #[inline]
+    fn drop(&mut self) {
+        let data = unsafe {
+            // SAFETY: self.data is never referenced again and it isn't dropped
+            ManuallyDrop::take(&mut self.data)
+        };
+        self.pool.recycle(data, &self.ticket);
+    }
+}
@@ -0,0 +1,153 @@
+use core::mem;
+use core::ops::{Deref, DerefMut};
+
+/// Guard of a pool projected into a part of its element, created by the `map`
+/// functions of the wrappers like [`crate::LinearReusable::map`].
+///
+/// Access to the part is allowed with [`std::ops::Deref`] or [`std::ops::DerefMut`],
+/// the whole element returns in its pool when it's dropped.
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::{LinearObjectPool, LinearReusable};
+///
+///  let pool = LinearObjectPool::<(u32, String)>::new(
+///    ||  Default::default(),
+///    |v| {
+///      v.1.clear();
+///    }
+///  );
+///  let mut item = LinearReusable::map(pool.pull(), |v| &mut v.1);
+///
+///  item.push_str("hello");
+///  assert_eq!(item.len(), 5);
+/// ```
+pub struct MappedReusable<G, U> {
+    guard: G,
+    target: Target<U>,
+}
+
+/// Location of the projected part.
+///
+/// A part reached through `&mut` of the element either lies in the bytes of
+/// the element or doesn't overlap them at all, so its address tells whether it
+/// moves with the element. A zero sized part has no bytes: its pointer stays
+/// valid wherever the element goes, so it's always kept as is.
+enum Target<U> {
+    /// Offset in the element, it moves with the guard when the element is
+    /// stored inline like in [`crate::MutexReusable`].
+    Inline(usize),
+    /// Outside of the element, like the buffer of a `Vec`, or zero sized.
+    Outside(*mut U),
+}
+
+impl<U> Target<U> {
+    /// Locate `value` relatively to `element`, before the guard is moved.
+    #[inline]
+    fn locate<T: ?Sized>(element: &T, value: *mut U) -> Self {
+        if mem::size_of::<U>() == 0 {
+            return Target::Outside(value);
+        }
+        let base = element as *const T as *const u8 as usize;
+        let offset = (value as usize).wrapping_sub(base);
+        match offset.checked_add(mem::size_of::<U>()) {
+            Some(end) if end <= mem::size_of_val(element) => Target::Inline(offset),
+            _ => Target::Outside(value),
+        }
+    }
+}
+
+impl<G, U> MappedReusable<G, U>
+where
+    G: DerefMut,
+{
+    /// Create new projection
+    ///
+    /// # Arguments
+    /// * `guard` wrapper of the element
+    /// * `f`     projection into the element
+    #[inline]
+    pub(crate) fn new<F>(mut guard: G, f: F) -> Self
+    where
+        F: FnOnce(&mut G::Target) -> &mut U,
+    {
+        let value: *mut U = f(&mut guard);
+        let target = Target::locate(&*guard, value);
+        Self { guard, target }
+    }
+
+    /// Make a new projection from a part of the projected part.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{MappedReusable, SpinLockObjectPool, SpinLockReusable};
+    ///
+    ///  let pool = SpinLockObjectPool::<((u32, u32), u32)>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = Default::default();
+    ///    }
+    ///  );
+    ///  let item = SpinLockReusable::map(pool.pull(), |v| &mut v.0);
+    ///  let mut item = MappedReusable::map(item, |v| &mut v.1);
+    ///  *item = 5;
+    /// ```
+    #[inline]
+    pub fn map<V, F>(mut this: Self, f: F) -> MappedReusable<G, V>
+    where
+        F: FnOnce(&mut U) -> &mut V,
+    {
+        let value: *mut V = f(&mut this);
+        let target = Target::locate(&*this.guard, value);
+        MappedReusable {
+            guard: this.guard,
+            target,
+        }
+    }
+}
+
+impl<G, U> DerefMut for MappedReusable<G, U>
+where
+    G: DerefMut,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self.target {
+            Target::Inline(offset) => unsafe {
+                // SAFETY: offset was checked to be in the element when the projection was made
+                &mut *(&mut *self.guard as *mut G::Target as *mut u8)
+                    .add(offset)
+                    .cast::<U>()
+            },
+            Target::Outside(value) => unsafe {
+                // SAFETY: the part is owned by the element, the guard is borrowed mutably
+                &mut *value
+            },
+        }
+    }
+}
+
+impl<G, U> Deref for MappedReusable<G, U>
+where
+    G: DerefMut,
+{
+    type Target = U;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match self.target {
+            Target::Inline(offset) => unsafe {
+                // SAFETY: offset was checked to be in the element when the projection was made
+                &*(&*self.guard as *const G::Target as *const u8)
+                    .add(offset)
+                    .cast::<U>()
+            },
+            Target::Outside(value) => unsafe {
+                // SAFETY: the part is owned by the element, the guard is borrowed
+                &*value
+            },
+        }
+    }
+}
+
+unsafe impl<G: Send, U: Send> Send for MappedReusable<G, U> {} // SAFETY: the part is owned by the element of the guard
+unsafe impl<G: Sync, U: Sync> Sync for MappedReusable<G, U> {} // SAFETY: the part is only reachable through the guard
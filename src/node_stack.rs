@@ -0,0 +1,307 @@
+use alloc::boxed::Box;
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicU32, AtomicU64, Ordering};
+
+/// Index of a node in a [`NodeStack`].
+pub type NodeId = u32;
+
+/// Number of nodes of the first segment, each segment is twice as big as the previous one.
+const FIRST_SEGMENT: u64 = 32;
+const NB_SEGMENTS: usize = 32;
+/// Value of a link to no node.
+const NONE: u32 = u32::MAX;
+
+struct Node<T> {
+    value: UnsafeCell<MaybeUninit<T>>,
+    next: AtomicU32,
+}
+
+/// Lock-free LIFO of nodes, the head packs the id of the first node with a
+/// generation incremented by each update, so that a node popped and pushed
+/// again between the load and the update of the head can't be mistaken for
+/// the old head (ABA problem).
+struct Head {
+    head: AtomicU64,
+}
+
+impl Head {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            head: AtomicU64::new(Self::pack(0, NONE)),
+        }
+    }
+
+    #[inline]
+    fn pack(generation: u32, id: u32) -> u64 {
+        (u64::from(generation) << 32) | u64::from(id)
+    }
+
+    #[inline]
+    fn unpack(head: u64) -> (u32, u32) {
+        ((head >> 32) as u32, head as u32)
+    }
+}
+
+/// Nodes allocated by segments which are never released before the stack,
+/// with a stack of the nodes holding an idle value and a stack of the nodes
+/// without value.
+///
+/// A node which isn't in one of the stacks is owned by its user.
+pub struct NodeStack<T> {
+    segments: [AtomicPtr<Node<T>>; NB_SEGMENTS],
+    nb_nodes: AtomicU32,
+    idle: Head,
+    vacant: Head,
+}
+
+impl<T> NodeStack<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            segments: Default::default(),
+            nb_nodes: AtomicU32::new(0),
+            idle: Head::new(),
+            vacant: Head::new(),
+        }
+    }
+
+    /// Take a node holding an idle value.
+    #[inline]
+    pub fn pop_idle(&self) -> Option<NodeId> {
+        self.pop(&self.idle)
+    }
+
+    /// Give back a node holding an idle value.
+    ///
+    /// # Safety
+    /// * `id` has to be owned by the caller and hold a value
+    #[inline]
+    pub unsafe fn push_idle(&self, id: NodeId) {
+        self.push(&self.idle, id);
+    }
+
+    /// Put `value` in a node owned by the caller.
+    #[inline]
+    pub fn insert(&self, value: T) -> NodeId {
+        let id = self.pop(&self.vacant).unwrap_or_else(|| self.alloc());
+        unsafe {
+            // SAFETY: the node is owned by the caller and has no value
+            (*self.node(id).value.get()).write(value);
+        }
+        id
+    }
+
+    /// Take the value out of a node, which becomes vacant.
+    ///
+    /// # Safety
+    /// * `id` has to be owned by the caller and hold a value
+    #[inline]
+    pub unsafe fn take(&self, id: NodeId) -> T {
+        let value = (*self.node(id).value.get()).assume_init_read();
+        self.push(&self.vacant, id);
+        value
+    }
+
+    /// Drop the value of a node, which becomes vacant.
+    ///
+    /// # Safety
+    /// * `id` has to be owned by the caller and hold a value
+    #[inline]
+    pub unsafe fn remove(&self, id: NodeId) {
+        drop(self.take(id));
+    }
+
+    /// # Safety
+    /// * `id` has to be owned by the caller and hold a value
+    #[inline]
+    pub unsafe fn get(&self, id: NodeId) -> &T {
+        (*self.node(id).value.get()).assume_init_ref()
+    }
+
+    /// # Safety
+    /// * `id` has to be owned by the caller and hold a value
+    #[inline]
+    #[allow(clippy::mut_from_ref)] // the function is marked as unsafe for a reason
+    pub unsafe fn get_mut(&self, id: NodeId) -> &mut T {
+        (*self.node(id).value.get()).assume_init_mut()
+    }
+
+    #[inline]
+    fn pop(&self, head: &Head) -> Option<NodeId> {
+        let mut current = head.head.load(Ordering::Acquire);
+        loop {
+            let (generation, id) = Head::unpack(current);
+            if id == NONE {
+                return None;
+            }
+            // the node may be popped concurrently, its link is then outdated
+            // but the generation of the head has changed
+            let next = self.node(id).next.load(Ordering::Relaxed);
+            match head.head.compare_exchange_weak(
+                current,
+                Head::pack(generation.wrapping_add(1), next),
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Some(id),
+                Err(head) => current = head,
+            }
+        }
+    }
+
+    #[inline]
+    fn push(&self, head: &Head, id: NodeId) {
+        let node = self.node(id);
+        let mut current = head.head.load(Ordering::Relaxed);
+        loop {
+            let (generation, next) = Head::unpack(current);
+            node.next.store(next, Ordering::Relaxed);
+            match head.head.compare_exchange_weak(
+                current,
+                Head::pack(generation.wrapping_add(1), id),
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return,
+                Err(head) => current = head,
+            }
+        }
+    }
+
+    /// Allocate a new node, owned by the caller.
+    #[inline]
+    fn alloc(&self) -> NodeId {
+        let id = self.nb_nodes.fetch_add(1, Ordering::Relaxed);
+        assert!(id != NONE, "too many elements in the pool");
+        let (segment, _) = Self::locate(id);
+        if self.segments[segment].load(Ordering::Acquire).is_null() {
+            let nodes: Box<[Node<T>]> = (0..Self::segment_len(segment))
+                .map(|_| Node {
+                    value: UnsafeCell::new(MaybeUninit::uninit()),
+                    next: AtomicU32::new(NONE),
+                })
+                .collect();
+            let nodes = Box::into_raw(nodes) as *mut Node<T>;
+            if self.segments[segment]
+                .compare_exchange(ptr::null_mut(), nodes, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+            {
+                unsafe {
+                    // SAFETY: nodes was allocated above and never shared
+                    Self::free_segment(nodes, segment);
+                }
+            }
+        }
+        id
+    }
+
+    #[inline]
+    fn node(&self, id: NodeId) -> &Node<T> {
+        let (segment, offset) = Self::locate(id);
+        unsafe {
+            // SAFETY: the segment of an allocated node is never released before the stack
+            &*self.segments[segment].load(Ordering::Acquire).add(offset)
+        }
+    }
+
+    /// Segment and offset in the segment of a node.
+    #[inline]
+    fn locate(id: NodeId) -> (usize, usize) {
+        let index = u64::from(id) + FIRST_SEGMENT;
+        let segment = (index.ilog2() - FIRST_SEGMENT.ilog2()) as usize;
+        (segment, (index - (FIRST_SEGMENT << segment)) as usize)
+    }
+
+    #[inline]
+    fn segment_len(segment: usize) -> usize {
+        (FIRST_SEGMENT << segment) as usize
+    }
+
+    /// # Safety
+    /// * `nodes` has to be allocated as the segment `segment`
+    #[inline]
+    unsafe fn free_segment(nodes: *mut Node<T>, segment: usize) {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            nodes,
+            Self::segment_len(segment),
+        )));
+    }
+}
+
+impl<T> Drop for NodeStack<T> {
+    fn drop(&mut self) {
+        // all the nodes are in one of the stacks, only the idle ones hold a value
+        while let Some(id) = self.pop_idle() {
+            unsafe {
+                // SAFETY: an idle node holds a value
+                (*self.node(id).value.get()).assume_init_drop();
+            }
+        }
+        for (segment, nodes) in self.segments.iter_mut().enumerate() {
+            let nodes = *nodes.get_mut();
+            if !nodes.is_null() {
+                unsafe {
+                    // SAFETY: nodes was allocated by alloc
+                    Self::free_segment(nodes, segment);
+                }
+            }
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for NodeStack<T> {} // SAFETY: sending the data is allowed if it's Send
+unsafe impl<T: Send> Sync for NodeStack<T> {} // SAFETY: a node has one owner at a time so only Send is required
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_stack_locate() {
+        assert_eq!(NodeStack::<u32>::locate(0), (0, 0));
+        assert_eq!(NodeStack::<u32>::locate(31), (0, 31));
+        assert_eq!(NodeStack::<u32>::locate(32), (1, 0));
+        assert_eq!(NodeStack::<u32>::locate(95), (1, 63));
+        assert_eq!(NodeStack::<u32>::locate(96), (2, 0));
+        assert_eq!(NodeStack::<u32>::locate(u32::MAX - 1).0, NB_SEGMENTS - 5);
+    }
+
+    #[test]
+    fn test_node_stack_lifo() {
+        let stack = NodeStack::new();
+        let ids: Vec<_> = (0..100).map(|v| stack.insert(v)).collect();
+        for id in &ids {
+            unsafe { stack.push_idle(*id) };
+        }
+        for v in (0..100).rev() {
+            let id = stack.pop_idle().unwrap();
+            assert_eq!(unsafe { *stack.get(id) }, v);
+            assert_eq!(unsafe { stack.take(id) }, v);
+        }
+        assert!(stack.pop_idle().is_none());
+        // the vacant nodes are reused
+        let id = stack.insert(5);
+        assert_eq!(id, ids[0]);
+        unsafe { stack.push_idle(id) };
+    }
+
+    #[test]
+    fn test_node_stack_drop() {
+        use std::rc::Rc;
+
+        let alive = Rc::new(());
+        let stack = NodeStack::new();
+        let a = stack.insert(alive.clone());
+        let b = stack.insert(alive.clone());
+        unsafe {
+            stack.push_idle(a);
+            drop(stack.take(b));
+        }
+        assert_eq!(Rc::strong_count(&alive), 2);
+        drop(stack);
+        assert_eq!(Rc::strong_count(&alive), 1);
+    }
+}
@@ -1,5 +1,22 @@
-use crate::none_reusable::NoneReusable;
-use std::sync::Arc;
+#[cfg(feature = "leak-check")]
+use crate::leak_check::Outstanding;
+#[cfg(feature = "stats")]
+use crate::stats::PoolStats;
+use crate::{
+    capacity::Capacity,
+    error::PoolError,
+    leak_check::{LeakTracker, Ticket},
+    none_reusable::NoneReusable,
+    object_pool::ObjectPool,
+    pool_builder::PoolBuilder,
+    pull_future::{PullFuture, TryPullFuture, WaitablePool},
+    stats::Stats,
+    wait_queue::WaitQueue,
+};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::error::Error;
+use core::panic::Location;
 
 /// Basic allocation without pull. Used to compare default rust allocation with different kind of object pool.
 /// # Example
@@ -13,7 +30,48 @@ use std::sync::Arc;
 ///  let work = *item * 5;
 /// ```
 pub struct NoneObjectPool<T> {
-    init: Box<dyn Fn() -> T + Send + Sync>,
+    init: Box<dyn Fn() -> Result<T, PoolError> + Send + Sync>,
+    health_check: Box<dyn Fn(&T) -> bool + Send + Sync>,
+    health_check_retries: usize,
+    shared: Arc<NoneShared>,
+}
+
+/// State of a [`NoneObjectPool`] referenced by its elements.
+pub(crate) struct NoneShared {
+    capacity: Capacity,
+    stats: Stats,
+    tracker: LeakTracker,
+    waiters: WaitQueue,
+}
+
+impl NoneShared {
+    /// Return false if the elements don't need to reference this state.
+    #[inline]
+    fn is_used(&self) -> bool {
+        self.capacity.is_bounded() || cfg!(feature = "stats") || cfg!(feature = "leak-check")
+    }
+
+    #[inline]
+    pub(crate) fn on_return(&self, ticket: &Ticket) {
+        self.tracker.untrack(ticket);
+        self.stats.on_return();
+        self.release_capacity();
+    }
+
+    #[inline]
+    pub(crate) fn on_detach(&self, ticket: &Ticket) {
+        self.tracker.untrack(ticket);
+        self.stats.on_detach();
+        self.release_capacity();
+    }
+
+    #[inline]
+    fn release_capacity(&self) {
+        if self.capacity.is_bounded() {
+            self.capacity.release();
+            self.waiters.notify_one();
+        }
+    }
 }
 
 impl<T> NoneObjectPool<T> {
@@ -34,9 +92,58 @@ impl<T> NoneObjectPool<T> {
     where
         I: Fn() -> T + Send + Sync + 'static,
     {
-        Self {
-            init: Box::new(init),
-        }
+        PoolBuilder::new(init).build()
+    }
+
+    ///
+    /// Create an new [`NoneObjectPool`] with at most `max_objects` elements alive.
+    ///
+    /// When the limit is reached, [`NoneObjectPool::try_pull`] fails and
+    /// [`NoneObjectPool::pull`] panics.
+    ///
+    /// # Arguments
+    /// * `init` closure to create new item
+    /// * `max_objects` maximum number of elements alive
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::NoneObjectPool;
+    ///
+    ///  let pool = NoneObjectPool::<u32>::new_bounded(|| Default::default(), 1);
+    ///  let item = pool.pull();
+    ///  assert!(pool.try_pull().is_err());
+    /// ```
+    #[inline]
+    pub fn new_bounded<I>(init: I, max_objects: usize) -> Self
+    where
+        I: Fn() -> T + Send + Sync + 'static,
+    {
+        PoolBuilder::new(init).max_objects(max_objects).build()
+    }
+
+    ///
+    /// Create an new [`NoneObjectPool`] whose elements creation can fail.
+    ///
+    /// The error of `init` is returned as [`PoolError::Init`] by the `try_pull` functions.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{NoneObjectPool, PoolError};
+    ///
+    ///  let pool = NoneObjectPool::<u32>::try_new(
+    ///    || "x".parse::<u32>()).unwrap();
+    ///  assert!(matches!(pool.try_pull(), Err(PoolError::Init(_))));
+    /// ```
+    #[inline]
+    pub fn try_new<I, E>(init: I) -> Result<Self, PoolError>
+    where
+        I: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        PoolBuilder::try_new(init).try_build()
     }
 
     ///
@@ -49,9 +156,51 @@ impl<T> NoneObjectPool<T> {
     ///  let pool = NoneObjectPool::<u32>::new(|| Default::default());
     ///  let mut item = pool.pull();
     /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are alive.
     #[inline]
+    #[track_caller]
     pub fn pull(&self) -> NoneReusable<T> {
-        NoneReusable::new((self.init)())
+        self.try_pull().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it doesn't return in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::NoneObjectPool;
+    ///
+    ///  let pool = NoneObjectPool::<u32>::new(|| Default::default());
+    ///  let mut item = pool.try_pull().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull(&self) -> Result<NoneReusable<T>, PoolError> {
+        self.try_pull_at(Location::caller())
+    }
+
+    #[inline]
+    fn try_pull_at(
+        &self,
+        location: &'static Location<'static>,
+    ) -> Result<NoneReusable<T>, PoolError> {
+        if !self.shared.capacity.acquire() {
+            return Err(PoolError::Exhausted);
+        }
+        match self.create() {
+            Ok(data) => {
+                self.shared.stats.on_pull(false);
+                let ticket = self.shared.tracker.track(location);
+                let shared = self.shared.is_used().then(|| self.shared.clone());
+                Ok(NoneReusable::from_pool(data, shared, ticket))
+            }
+            Err(err) => {
+                self.shared.release_capacity();
+                Err(err)
+            }
+        }
     }
 
     ///
@@ -65,8 +214,192 @@ impl<T> NoneObjectPool<T> {
     ///  let pool = Arc::new(NoneObjectPool::<u32>::new(|| Default::default()));
     ///  let mut item = pool.pull_owned();
     /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are alive.
     #[inline]
+    #[track_caller]
     pub fn pull_owned(self: &Arc<Self>) -> NoneReusable<T> {
-        NoneReusable::new((self.init)())
+        self.pull()
+    }
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it doesn't return in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use std::sync::Arc;
+    ///  use lockfree_object_pool::NoneObjectPool;
+    ///
+    ///  let pool = Arc::new(NoneObjectPool::<u32>::new(|| Default::default()));
+    ///  let mut item = pool.try_pull_owned().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_owned(self: &Arc<Self>) -> Result<NoneReusable<T>, PoolError> {
+        self.try_pull()
+    }
+
+    ///
+    /// Create a new element, waiting asynchronously for an element to be dropped if the pool is exhausted.
+    /// When the element is dropped, it doesn't return in the pull.
+    ///
+    /// The returned future doesn't depend on any executor, an unbounded pool never waits.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::NoneObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  async fn work(pool: Arc<NoneObjectPool<u32>>) {
+    ///    let mut item = pool.pull_async().await;
+    ///    *item = 5;
+    ///  }
+    /// ```
+    ///
+    /// # Panics
+    /// When polled, if the element can't be created or fails its health check.
+    #[inline]
+    #[track_caller]
+    pub fn pull_async(self: &Arc<Self>) -> PullFuture<T, Self> {
+        PullFuture::new(self.clone(), Location::caller())
+    }
+
+    ///
+    /// Same as `pull_async`, the future resolving to the error if the element
+    /// can't be created or fails its health check.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{NoneObjectPool, PoolError};
+    ///  use std::sync::Arc;
+    ///
+    ///  async fn work(pool: Arc<NoneObjectPool<u32>>) -> Result<(), PoolError> {
+    ///    let mut item = pool.try_pull_async().await?;
+    ///    *item = 5;
+    ///    Ok(())
+    ///  }
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_async(self: &Arc<Self>) -> TryPullFuture<T, Self> {
+        TryPullFuture::new(self.clone(), Location::caller())
+    }
+
+    /// Create an element passing the health check.
+    #[inline]
+    fn create(&self) -> Result<T, PoolError> {
+        let mut data = self.call_init()?;
+        let mut retries = self.health_check_retries;
+        while !(self.health_check)(&data) {
+            if retries == 0 {
+                return Err(PoolError::Unhealthy);
+            }
+            retries -= 1;
+            data = self.call_init()?;
+        }
+        Ok(data)
+    }
+
+    #[inline]
+    fn call_init(&self) -> Result<T, PoolError> {
+        self.shared.stats.on_init();
+        (self.init)()
+    }
+
+    ///
+    /// Elements still alive with the call site of their pull, the oldest first.
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    pub fn outstanding(&self) -> Vec<Outstanding> {
+        self.shared.tracker.outstanding()
+    }
+
+    ///
+    /// Snapshot of the statistics of the pool, `hits`, `idle` and `pages` are always 0.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> PoolStats {
+        self.shared.stats.snapshot(0, 0)
+    }
+}
+
+impl<T> ObjectPool<T> for NoneObjectPool<T> {
+    type Reusable<'a>
+        = NoneReusable<T>
+    where
+        Self: 'a;
+    type OwnedReusable = NoneReusable<T>;
+
+    #[inline]
+    #[track_caller]
+    fn pull(&self) -> Self::Reusable<'_> {
+        NoneObjectPool::pull(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn pull_owned(self: &Arc<Self>) -> Self::OwnedReusable {
+        NoneObjectPool::pull_owned(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn try_pull(&self) -> Result<Self::Reusable<'_>, PoolError> {
+        NoneObjectPool::try_pull(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn try_pull_owned(self: &Arc<Self>) -> Result<Self::OwnedReusable, PoolError> {
+        NoneObjectPool::try_pull_owned(self)
+    }
+
+    #[cfg(feature = "stats")]
+    #[inline]
+    fn stats(&self) -> PoolStats {
+        NoneObjectPool::stats(self)
+    }
+
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    fn outstanding(&self) -> Vec<Outstanding> {
+        NoneObjectPool::outstanding(self)
+    }
+}
+
+impl<T> WaitablePool<T> for NoneObjectPool<T> {
+    #[inline]
+    fn waiters(&self) -> &WaitQueue {
+        &self.shared.waiters
+    }
+
+    #[inline]
+    fn try_pull_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Result<Self::OwnedReusable, PoolError> {
+        self.try_pull_at(location)
+    }
+}
+
+/// Only `init`, `max_objects` and the health check are used, the elements are never reused.
+impl<T> TryFrom<PoolBuilder<T>> for NoneObjectPool<T> {
+    type Error = PoolError;
+
+    #[inline]
+    fn try_from(builder: PoolBuilder<T>) -> Result<Self, Self::Error> {
+        let shared = Arc::new(NoneShared {
+            capacity: builder.capacity(),
+            stats: Stats::default(),
+            tracker: builder.leak_tracker(),
+            waiters: WaitQueue::new(),
+        });
+        Ok(Self {
+            init: builder.init,
+            health_check: builder.health_check,
+            health_check_retries: builder.health_check_retries,
+            shared,
+        })
     }
 }
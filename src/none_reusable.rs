@@ -1,7 +1,13 @@
-use std::ops::{Deref, DerefMut};
+use alloc::sync::Arc;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+use core::ptr;
 
+use crate::leak_check::Ticket;
+use crate::mapped_reusable::MappedReusable;
 #[allow(unused_imports)]
 use crate::none_object_pool::NoneObjectPool;
+use crate::none_object_pool::NoneShared;
 
 /// Wrapper over T used by [`NoneObjectPool`].
 ///
@@ -17,7 +23,8 @@ use crate::none_object_pool::NoneObjectPool;
 ///  let work = *item * 5;
 /// ```
 pub struct NoneReusable<T> {
-    data: T,
+    data: ManuallyDrop<T>,
+    shared: Option<(Arc<NoneShared>, Ticket)>,
 }
 
 impl<T> NoneReusable<T> {
@@ -27,7 +34,63 @@ impl<T> NoneReusable<T> {
     /// * `data` element to wrappe
     #[inline]
     pub fn new(data: T) -> Self {
-        Self { data }
+        Self {
+            data: ManuallyDrop::new(data),
+            shared: None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn from_pool(data: T, shared: Option<Arc<NoneShared>>, ticket: Ticket) -> Self {
+        Self {
+            data: ManuallyDrop::new(data),
+            shared: shared.map(|shared| (shared, ticket)),
+        }
+    }
+
+    /// Take the element out of the wrapper.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::NoneObjectPool;
+    ///
+    ///  let pool = NoneObjectPool::<u32>::new(|| Default::default());
+    ///  let mut item = pool.pull();
+    ///  *item = 5;
+    ///  let data: u32 = item.detach();
+    ///  assert_eq!(data, 5);
+    /// ```
+    #[inline]
+    pub fn detach(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        unsafe {
+            // SAFETY: this is never dropped so each field is moved out once
+            if let Some((shared, ticket)) = ptr::read(&this.shared) {
+                shared.on_detach(&ticket);
+            }
+            ManuallyDrop::take(&mut this.data)
+        }
+    }
+
+    /// Make a guard of a part of the element, like `std::cell::RefMut::map`.
+    ///
+    /// This is an associated function to not shadow the functions of the element.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{NoneObjectPool, NoneReusable};
+    ///
+    ///  let pool = NoneObjectPool::<(u32, String)>::new(|| Default::default());
+    ///  let mut item = NoneReusable::map(pool.pull(), |v| &mut v.1);
+    ///  item.push_str("hello");
+    ///  assert_eq!(item.len(), 5);
+    /// ```
+    #[inline]
+    pub fn map<U, F>(this: Self, f: F) -> MappedReusable<Self, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedReusable::new(this, f)
     }
 }
 
@@ -46,3 +109,16 @@ impl<T> Deref for NoneReusable<T> {
         &self.data
     }
 }
+
+impl<T> Drop for NoneReusable<T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            // SAFETY: self.data is never referenced again
+            ManuallyDrop::drop(&mut self.data)
+        };
+        if let Some((shared, ticket)) = &self.shared {
+            shared.on_return(ticket);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::error::PoolError;
+#[cfg(feature = "leak-check")]
+use crate::leak_check::Outstanding;
+#[cfg(feature = "stats")]
+use crate::stats::PoolStats;
+use alloc::sync::Arc;
+use core::ops::DerefMut;
+
+/// Common interface of all object pool implementations.
+///
+/// It allows to write code generic over the pool strategy and to choose
+/// the implementation ([`crate::LinearObjectPool`], [`crate::SpinLockObjectPool`],
+/// [`crate::MutexObjectPool`] or [`crate::NoneObjectPool`]) by configuration.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::{LinearObjectPool, ObjectPool, SpinLockObjectPool};
+///
+///  fn work<P: ObjectPool<u32>>(pool: &P) -> u32 {
+///    let mut item = pool.pull();
+///    *item += 5;
+///    *item * 5
+///  }
+///
+///  let linear = LinearObjectPool::<u32>::new(Default::default, |v| *v = 0);
+///  let spin_lock = SpinLockObjectPool::<u32>::new(Default::default, |v| *v = 0);
+///  assert_eq!(work(&linear), work(&spin_lock));
+/// ```
+pub trait ObjectPool<T> {
+    /// Wrapper returned by [`ObjectPool::pull`].
+    type Reusable<'a>: DerefMut<Target = T>
+    where
+        Self: 'a;
+
+    /// Wrapper returned by [`ObjectPool::pull_owned`].
+    type OwnedReusable: DerefMut<Target = T>;
+
+    ///
+    /// Create a new element. When the element is dropped, it returns in the pull.
+    #[track_caller]
+    fn pull(&self) -> Self::Reusable<'_>;
+
+    ///
+    /// Create a new element. When the element is dropped, it returns in the pull.
+    #[track_caller]
+    fn pull_owned(self: &Arc<Self>) -> Self::OwnedReusable;
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it returns in the pull.
+    #[track_caller]
+    fn try_pull(&self) -> Result<Self::Reusable<'_>, PoolError>;
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it returns in the pull.
+    #[track_caller]
+    fn try_pull_owned(self: &Arc<Self>) -> Result<Self::OwnedReusable, PoolError>;
+
+    ///
+    /// Snapshot of the statistics of the pool.
+    ///
+    /// All zero for a pool which doesn't count, so that enabling the `stats`
+    /// feature doesn't break the implementations of other crates.
+    #[cfg(feature = "stats")]
+    #[inline]
+    fn stats(&self) -> PoolStats {
+        PoolStats::default()
+    }
+
+    ///
+    /// Elements checked out with the call site of their pull, the oldest first.
+    ///
+    /// Empty for a pool which doesn't track its elements, so that enabling the
+    /// `leak-check` feature doesn't break the implementations of other crates.
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    fn outstanding(&self) -> Vec<Outstanding> {
+        Vec::new()
+    }
+}
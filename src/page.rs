@@ -1,58 +1,92 @@
-use std::{
+use core::{
     cell::UnsafeCell,
-    sync::atomic::{AtomicU32, Ordering},
+    mem::MaybeUninit,
+    sync::atomic::{fence, AtomicU32, AtomicU64, Ordering},
 };
 
-pub struct Page<T> {
-    data: [UnsafeCell<T>; 32],
-    free: AtomicU32,
+/// Fixed array of `N` elements with a bitmap of the free ones, `N` is at most 64.
+///
+/// The elements are created by their first allocation, a second bitmap tracks
+/// the initialized ones.
+pub struct Page<T, const N: usize = 32> {
+    data: [Slot<T>; N],
+    free: AtomicU64,
+    init: AtomicU64,
+}
+
+/// Element of a page with the number of its shared references.
+struct Slot<T> {
+    value: UnsafeCell<MaybeUninit<T>>,
+    refs: AtomicU32,
+}
+
+impl<T> Slot<T> {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+            refs: AtomicU32::new(0),
+        }
+    }
 }
 
 pub type PageId = u8;
 
-impl<T> Page<T> {
+impl<T, const N: usize> Page<T, N> {
+    /// Number of elements in a page.
+    pub const CAPACITY: usize = N;
+
+    /// Bitmap of a page whose elements are all free.
+    const FULL: u64 = {
+        assert!(N > 0 && N <= 64, "a page holds from 1 to 64 elements");
+        u64::MAX >> (64 - N)
+    };
+
+    /// Create a page of free and uninitialized elements.
     #[inline]
-    pub fn new<I>(init: I) -> Self
+    pub fn new() -> Self {
+        Self {
+            data: core::array::from_fn(|_| Slot::new()),
+            free: AtomicU64::new(Self::FULL),
+            init: AtomicU64::new(0),
+        }
+    }
+
+    /// Initialize at most `count` free elements with `init`, return the number
+    /// of initialized elements.
+    ///
+    /// # Safety
+    /// * no element of the page has to be allocated
+    #[inline]
+    pub unsafe fn fill<I, E>(&self, init: I, count: usize) -> Result<usize, E>
     where
-        I: Fn() -> T,
+        I: Fn() -> Result<T, E>,
     {
-        Self {
-            data: [
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-                UnsafeCell::new(init()),
-            ],
-            free: AtomicU32::new(u32::MAX),
+        let mut filled = 0;
+        for id in 0..N as PageId {
+            if filled == count {
+                break;
+            }
+            if !self.is_init(&id) {
+                self.write(&id, init()?);
+                filled += 1;
+            }
         }
+        Ok(filled)
+    }
+
+    /// Number of free and initialized elements.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn nb_idle(&self) -> usize {
+        (self.free.load(Ordering::Relaxed) & self.init.load(Ordering::Relaxed)).count_ones()
+            as usize
+    }
+
+    /// Number of initialized elements.
+    #[inline]
+    pub fn nb_init(&self) -> usize {
+        self.init.load(Ordering::Relaxed).count_ones() as usize
     }
 
     #[cfg(test)]
@@ -61,7 +95,7 @@ impl<T> Page<T> {
     }
 
     #[cfg(test)]
-    pub(crate) fn get_mask(&self) -> u32 {
+    pub(crate) fn get_mask(&self) -> u64 {
         self.free.load(Ordering::Relaxed)
     }
 
@@ -79,40 +113,142 @@ impl<T> Page<T> {
             .map(|free| free.trailing_zeros() as u8)
     }
 
+    /// Allocate a free element which isn't initialized.
+    ///
+    /// The bitmap of the initialized elements is read before the allocation,
+    /// the caller has to check that the element is still not initialized.
+    #[inline]
+    pub fn alloc_uninit(&self) -> Option<PageId> {
+        let init = self.init.load(Ordering::Relaxed);
+        self.free
+            .fetch_update(Ordering::SeqCst, Ordering::Relaxed, |free| {
+                let uninit = free & !init;
+                if uninit == 0 {
+                    None
+                } else {
+                    Some(free & !(uninit & uninit.wrapping_neg()))
+                }
+            })
+            .ok()
+            .map(|free| (free & !init).trailing_zeros() as u8)
+    }
+
+    /// Free an element, return true if the page was full.
+    #[inline]
+    pub fn free(&self, id: &PageId) -> bool {
+        let mask: u64 = 1 << id;
+        self.free.fetch_or(mask, Ordering::SeqCst) == 0
+    }
+
+    /// Return true if an element is free.
+    #[inline]
+    pub fn has_free(&self) -> bool {
+        self.free.load(Ordering::SeqCst) != 0
+    }
+
+    /// Return true if the element has been initialized, it's only reliable
+    /// for an element allocated by the caller.
+    #[inline]
+    pub fn is_init(&self, id: &PageId) -> bool {
+        self.init.load(Ordering::Relaxed) & (1 << id) != 0
+    }
+
+    /// Initialize an element.
+    ///
+    /// # Safety
+    /// * `id` has to be allocated by the caller and not initialized
+    #[inline]
+    pub unsafe fn write(&self, id: &PageId, value: T) {
+        (*self.data[*id as usize].value.get()).write(value);
+        self.init.fetch_or(1 << id, Ordering::Relaxed);
+    }
+
+    /// Move an element out, it's no longer initialized.
+    ///
+    /// # Safety
+    /// * `id` has to be allocated by the caller and initialized
     #[inline]
-    pub fn free(&self, id: &PageId) {
-        let mask: u32 = 1 << id;
-        self.free.fetch_or(mask, Ordering::SeqCst);
+    pub unsafe fn take(&self, id: &PageId) -> T {
+        self.init.fetch_and(!(1 << id), Ordering::Relaxed);
+        (*self.data[*id as usize].value.get()).assume_init_read()
     }
 
+    /// # Safety
+    /// * `id` has to be allocated by the caller and initialized
     #[inline]
     pub unsafe fn get(&self, id: &PageId) -> &T {
-        &*self.data[*id as usize].get()
+        (*self.data[*id as usize].value.get()).assume_init_ref()
     }
 
+    /// # Safety
+    /// * `id` has to be allocated by the caller and initialized
     #[inline]
     #[allow(clippy::mut_from_ref)] // the function is marked as unsafe for a reason
     pub unsafe fn get_mut(&self, id: &PageId) -> &mut T {
-        &mut *self.data[*id as usize].get()
+        (*self.data[*id as usize].value.get()).assume_init_mut()
+    }
+
+    /// Start sharing an allocated element with a single reference.
+    #[inline]
+    pub fn share(&self, id: &PageId) {
+        self.data[*id as usize].refs.store(1, Ordering::Relaxed);
+    }
+
+    /// Add a reference to a shared element.
+    #[inline]
+    pub fn add_ref(&self, id: &PageId) {
+        let refs = self.data[*id as usize].refs.fetch_add(1, Ordering::Relaxed);
+        if refs > u32::MAX / 2 {
+            // as Arc, the count can only overflow if the references are leaked
+            #[cfg(feature = "std")]
+            std::process::abort();
+            #[cfg(not(feature = "std"))]
+            panic!("reference count overflow");
+        }
+    }
+
+    /// Remove a reference to a shared element, return true if it was the last one.
+    #[inline]
+    pub fn remove_ref(&self, id: &PageId) -> bool {
+        if self.data[*id as usize].refs.fetch_sub(1, Ordering::Release) != 1 {
+            return false;
+        }
+        fence(Ordering::Acquire);
+        true
     }
 }
 
-unsafe impl<T: Send> Send for Page<T> {} // normal rules apply
-unsafe impl<T: Sync> Sync for Page<T> {} // normal rules apply
+impl<T, const N: usize> Drop for Page<T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        let mut init = *self.init.get_mut();
+        while init != 0 {
+            let id = init.trailing_zeros() as usize;
+            init &= init - 1;
+            unsafe {
+                // SAFETY: the element is initialized and the page is borrowed mutably
+                self.data[id].value.get_mut().assume_init_drop();
+            }
+        }
+    }
+}
+
+unsafe impl<T: Send, const N: usize> Send for Page<T, N> {} // normal rules apply
+unsafe impl<T: Sync, const N: usize> Sync for Page<T, N> {} // normal rules apply
 
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
     fn test_page_01() {
-        let page = Page::<u32>::new(|| 0);
+        let page = Page::<u32>::new();
         assert!(!page.is_full());
-        assert_eq!(page.get_mask(), u32::MAX);
+        assert_eq!(page.get_mask(), u64::from(u32::MAX));
     }
 
     #[test]
     fn test_page_02() {
-        let page = Page::<u32>::new(|| 0);
+        let page = Page::<u32>::new();
 
         let item1 = page.alloc();
         assert!(item1.is_some());
@@ -159,7 +295,7 @@ mod tests {
 
     #[test]
     fn test_page_03() {
-        let page = Page::<u32>::new(|| 0);
+        let page = Page::<u32>::new();
         for i in 0..32 {
             assert!(!page.is_full());
 
@@ -171,4 +307,75 @@ mod tests {
         let item = page.alloc();
         assert!(item.is_none());
     }
+
+    #[test]
+    fn test_page_width() {
+        let page = Page::<u32, 8>::new();
+        assert_eq!(page.get_mask(), 0xff);
+        assert_eq!((0..8).filter_map(|_| page.alloc()).max(), Some(7));
+        assert!(page.is_full());
+
+        let page = Page::<u32, 64>::new();
+        assert_eq!(page.get_mask(), u64::MAX);
+        assert_eq!((0..64).filter_map(|_| page.alloc()).max(), Some(63));
+        assert!(page.is_full());
+        page.free(&63);
+        assert_eq!(page.alloc(), Some(63));
+    }
+
+    #[test]
+    fn test_page_refs() {
+        let page = Page::<u32>::new();
+        let id = page.alloc().unwrap();
+        page.share(&id);
+        page.add_ref(&id);
+        assert!(!page.remove_ref(&id));
+        assert!(page.remove_ref(&id));
+    }
+
+    #[test]
+    fn test_page_04() {
+        use std::rc::Rc;
+
+        let alive = Rc::new(());
+        let created = std::cell::Cell::new(0);
+        let page = Page::<_>::new();
+        let filled = unsafe {
+            page.fill(
+                || {
+                    created.set(created.get() + 1);
+                    if created.get() > 10 {
+                        Err(())
+                    } else {
+                        Ok(alive.clone())
+                    }
+                },
+                32,
+            )
+        };
+        assert!(filled.is_err());
+        assert_eq!(Rc::strong_count(&alive), 11);
+        // only the initialized elements are dropped
+        drop(page);
+        assert_eq!(Rc::strong_count(&alive), 1);
+    }
+
+    #[test]
+    fn test_page_lazy_init() {
+        let page = Page::<String, 8>::new();
+        let id = page.alloc().unwrap();
+        assert!(!page.is_init(&id));
+        unsafe { page.write(&id, "a".to_string()) };
+        assert!(page.is_init(&id));
+        assert_eq!(unsafe { page.take(&id) }, "a");
+        assert!(!page.is_init(&id));
+        unsafe { page.write(&id, "b".to_string()) };
+        page.free(&id);
+        assert_eq!(
+            unsafe { page.fill(|| Ok::<_, ()>("c".to_string()), 3) },
+            Ok(3)
+        );
+        assert!((0..4).all(|id| page.is_init(&id)));
+        assert!(!page.is_init(&4));
+    }
 }
@@ -0,0 +1,464 @@
+use crate::capacity::Capacity;
+use crate::error::PoolError;
+use crate::leak_check::LeakTracker;
+use crate::wait_queue::WaitQueue;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::error::Error;
+
+/// When the `reset` closure is called on an element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResetMode {
+    /// Reset the element when it returns in the pool (default).
+    #[default]
+    OnReturn,
+    /// Reset the element when it's pulled again from the pool. The cost of
+    /// the reset is paid by the next user instead of the thread returning
+    /// the element.
+    OnPull,
+}
+
+/// How many elements a pool creates when it has no idle element left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Growth {
+    /// Create elements one by one (default). [`crate::LinearObjectPool`]
+    /// adds one page at a time.
+    #[default]
+    OnDemand,
+    /// Create this number of elements at once, the extra ones are kept idle.
+    /// [`crate::LinearObjectPool`] adds enough pages to hold them, their
+    /// elements being created by their first pull.
+    Batch(usize),
+}
+
+impl Growth {
+    /// Number of elements created when the pool grows.
+    #[inline]
+    pub(crate) fn batch_size(&self) -> usize {
+        match self {
+            Growth::OnDemand => 1,
+            Growth::Batch(size) => (*size).max(1),
+        }
+    }
+}
+
+/// Builder of all kinds of object pools.
+///
+/// Any pool implementing `TryFrom<PoolBuilder<T>>` can be built, so the pool kind
+/// can be chosen by configuration.
+///
+/// A setting which doesn't apply to the built pool is ignored, each setting lists
+/// the pools using it. A [`crate::ShardedObjectPool`] hands the settings over to
+/// its shards. The page width of a [`crate::LinearObjectPool`] isn't a setting
+/// but its const parameter `N`, e.g. `LinearObjectPool<T, 64>`.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::{LinearObjectPool, PoolBuilder, ResetMode, SpinLockObjectPool};
+///
+///  let builder = || {
+///    PoolBuilder::new(|| Vec::<u8>::with_capacity(4096))
+///      .reset(|v| v.clear())
+///      .reset_mode(ResetMode::OnPull)
+///      .prewarm(64)
+///      .max_objects(256)
+///  };
+///
+///  let linear: LinearObjectPool<Vec<u8>> = builder().build();
+///  let spin_lock: SpinLockObjectPool<Vec<u8>> = builder().build();
+///  let mut item = linear.pull();
+///  item.push(5);
+/// ```
+pub struct PoolBuilder<T> {
+    pub(crate) init: Box<dyn Fn() -> Result<T, PoolError> + Send + Sync>,
+    pub(crate) reset: Box<dyn Fn(&mut T) + Send + Sync>,
+    pub(crate) validate: Box<dyn Fn(&T) -> bool + Send + Sync>,
+    pub(crate) health_check: Box<dyn Fn(&T) -> bool + Send + Sync>,
+    pub(crate) health_check_retries: usize,
+    pub(crate) prewarm: usize,
+    pub(crate) max_objects: Option<usize>,
+    pub(crate) max_idle: Option<usize>,
+    pub(crate) reset_mode: ResetMode,
+    pub(crate) growth: Growth,
+    pub(crate) thread_cache: usize,
+    pub(crate) striping: bool,
+    pub(crate) fair_lock: bool,
+    pub(crate) shards: Option<usize>,
+    /// Wait queue shared by the shards of a sharded pool.
+    pub(crate) waiters: Option<Arc<WaitQueue>>,
+    #[cfg(feature = "leak-check")]
+    pub(crate) assert_no_leak: bool,
+}
+
+impl<T> PoolBuilder<T> {
+    ///
+    /// Create an new [`PoolBuilder`]
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    #[inline]
+    pub fn new<I>(init: I) -> Self
+    where
+        I: Fn() -> T + Send + Sync + 'static,
+    {
+        Self::with_init(Box::new(move || Ok(init())))
+    }
+
+    ///
+    /// Create an new [`PoolBuilder`] whose elements creation can fail.
+    ///
+    /// The error is returned as [`PoolError::Init`] by the `try_pull` functions
+    /// of the pool and by [`PoolBuilder::try_build`].
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{PoolBuilder, PoolError, SpinLockObjectPool};
+    ///
+    ///  let pool: SpinLockObjectPool<u32> = PoolBuilder::try_new(|| "5".parse::<u32>())
+    ///    .try_build()
+    ///    .unwrap();
+    ///  assert_eq!(*pool.try_pull().unwrap(), 5);
+    ///
+    ///  let pool: Result<SpinLockObjectPool<u32>, _> = PoolBuilder::try_new(|| "x".parse::<u32>())
+    ///    .prewarm(1)
+    ///    .try_build();
+    ///  assert!(matches!(pool, Err(PoolError::Init(_))));
+    /// ```
+    #[inline]
+    pub fn try_new<I, E>(init: I) -> Self
+    where
+        I: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Self::with_init(Box::new(move || {
+            init().map_err(|err| PoolError::Init(err.into()))
+        }))
+    }
+
+    #[inline]
+    fn with_init(init: Box<dyn Fn() -> Result<T, PoolError> + Send + Sync>) -> Self {
+        Self {
+            init,
+            reset: Box::new(|_| {}),
+            validate: Box::new(|_| true),
+            health_check: Box::new(|_| true),
+            health_check_retries: 3,
+            prewarm: 0,
+            max_objects: None,
+            max_idle: None,
+            reset_mode: ResetMode::default(),
+            growth: Growth::default(),
+            thread_cache: 0,
+            striping: false,
+            fair_lock: false,
+            shards: None,
+            waiters: None,
+            #[cfg(feature = "leak-check")]
+            assert_no_leak: false,
+        }
+    }
+
+    ///
+    /// Set the closure to reset item before reusage, nothing is done by default.
+    ///
+    /// Used by all the pools but [`crate::NoneObjectPool`], which never reuses an element.
+    #[inline]
+    pub fn reset<R>(mut self, reset: R) -> Self
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+    {
+        self.reset = Box::new(reset);
+        self
+    }
+
+    ///
+    /// Set the closure checking an element when it returns in the pool, every
+    /// element is kept by default.
+    ///
+    /// An element rejected by `validate` is dropped instead of being reused.
+    ///
+    /// Used by all the pools but [`crate::NoneObjectPool`], which never reuses an element.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{PoolBuilder, SpinLockObjectPool};
+    ///
+    ///  let pool: SpinLockObjectPool<Vec<u8>> = PoolBuilder::new(Vec::new)
+    ///    .reset(|v| v.clear())
+    ///    .validate(|v| v.capacity() <= 4096)
+    ///    .build();
+    ///  let mut item = pool.pull();
+    ///  item.resize(8192, 0);
+    ///  drop(item);
+    ///  assert_eq!(pool.pull().capacity(), 0);
+    /// ```
+    #[inline]
+    pub fn validate<V>(mut self, validate: V) -> Self
+    where
+        V: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.validate = Box::new(validate);
+        self
+    }
+
+    ///
+    /// Set the closure checking an element before it's handed out by a pull,
+    /// every element is healthy by default.
+    ///
+    /// An element failing `health_check` is dropped and replaced by a new one
+    /// created by `init`. When [`PoolBuilder::health_check_retries`] new elements
+    /// failed too, the pull fails with [`PoolError::Unhealthy`].
+    ///
+    /// Used by all the pools.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{PoolBuilder, SpinLockObjectPool};
+    ///  use std::time::{Duration, Instant};
+    ///
+    ///  let pool: SpinLockObjectPool<Instant> = PoolBuilder::new(Instant::now)
+    ///    .health_check(|created| created.elapsed() < Duration::from_secs(60))
+    ///    .build();
+    ///  let item = pool.pull();
+    ///  assert!(item.elapsed() < Duration::from_secs(60));
+    /// ```
+    #[inline]
+    pub fn health_check<C>(mut self, health_check: C) -> Self
+    where
+        C: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.health_check = Box::new(health_check);
+        self
+    }
+
+    ///
+    /// Set how many new elements are created to replace an element failing the
+    /// health check before the pull fails, 3 by default.
+    ///
+    /// Used by all the pools.
+    #[inline]
+    pub fn health_check_retries(mut self, retries: usize) -> Self {
+        self.health_check_retries = retries;
+        self
+    }
+
+    ///
+    /// Create `count` elements when the pool is built, so that the first pulls
+    /// don't pay the `init` cost. It's limited by [`PoolBuilder::max_objects`]
+    /// and [`PoolBuilder::max_idle`].
+    ///
+    /// Used by all the pools but [`crate::NoneObjectPool`], which never reuses an element.
+    #[inline]
+    pub fn prewarm(mut self, count: usize) -> Self {
+        self.prewarm = count;
+        self
+    }
+
+    ///
+    /// Limit the number of elements of the pool, unbounded by default.
+    ///
+    /// cf `new_bounded` of each pool for the exact meaning.
+    ///
+    /// Used by all the pools.
+    #[inline]
+    pub fn max_objects(mut self, max_objects: usize) -> Self {
+        self.max_objects = Some(max_objects);
+        self
+    }
+
+    ///
+    /// Limit the number of idle elements kept by the pool, unlimited by default.
+    /// An element returned while the pool already holds `max_idle` idle elements
+    /// is dropped.
+    ///
+    /// Only used by [`crate::MutexObjectPool`], [`crate::SpinLockObjectPool`]
+    /// and [`crate::StackObjectPool`].
+    #[inline]
+    pub fn max_idle(mut self, max_idle: usize) -> Self {
+        self.max_idle = Some(max_idle);
+        self
+    }
+
+    ///
+    /// Set when the `reset` closure is called, [`ResetMode::OnReturn`] by default.
+    ///
+    /// Used by all the pools but [`crate::NoneObjectPool`], which never reuses an element.
+    #[inline]
+    pub fn reset_mode(mut self, reset_mode: ResetMode) -> Self {
+        self.reset_mode = reset_mode;
+        self
+    }
+
+    ///
+    /// Set how many elements are created when the pool has no idle element,
+    /// [`Growth::OnDemand`] by default.
+    ///
+    /// Used by all the pools but [`crate::NoneObjectPool`], which never reuses an element.
+    #[inline]
+    pub fn growth(mut self, growth: Growth) -> Self {
+        self.growth = growth;
+        self
+    }
+
+    ///
+    /// Set how many idle elements are cached per thread, 0 (no cache) by
+    /// default. A pull and a drop on the same thread then usually don't touch
+    /// the state shared by all the threads, but a cached element can't be
+    /// pulled by another thread until the cache overflows or its thread exits.
+    ///
+    /// Only used by an unbounded [`crate::LinearObjectPool`]: a bounded pool
+    /// ignores it, as it has to see each returned element to wake up its
+    /// waiters. Ignored without the `std` feature.
+    #[inline]
+    pub fn thread_cache(mut self, size: usize) -> Self {
+        self.thread_cache = size;
+        self
+    }
+
+    ///
+    /// Start the search of a free element at a different page for each thread,
+    /// disabled by default. The threads pulling concurrently then usually don't
+    /// update the same page, the first pages are still searched when the
+    /// following ones are full.
+    ///
+    /// Only used by [`crate::LinearObjectPool`], ignored without the `std`
+    /// feature.
+    #[inline]
+    pub fn striping(mut self, striping: bool) -> Self {
+        self.striping = striping;
+        self
+    }
+
+    ///
+    /// Grant the lock of the pool in the order of the requests, disabled by
+    /// default. A waiting thread is then served before the threads arriving
+    /// after it instead of possibly starving, at the cost of some throughput.
+    ///
+    /// Only used by [`crate::SpinLockObjectPool`] and the sharded pools made of
+    /// them. [`crate::MutexObjectPool`] ignores it, the order of its
+    /// `std::sync::Mutex` is left to the platform.
+    #[inline]
+    pub fn fair_lock(mut self, fair_lock: bool) -> Self {
+        self.fair_lock = fair_lock;
+        self
+    }
+
+    ///
+    /// Set the number of shards, the number of CPUs rounded up to a power of two
+    /// by default. The limits of the pool are split between the shards, and a
+    /// bounded pool has at most `max_objects` shards so that none is empty.
+    ///
+    /// Only used by [`crate::ShardedObjectPool`].
+    #[inline]
+    pub fn shards(mut self, shards: usize) -> Self {
+        self.shards = Some(shards.max(1));
+        self
+    }
+
+    ///
+    /// Panic when the pool is dropped while elements are still checked out,
+    /// typically because a guard has been forgotten. Disabled by default.
+    ///
+    /// Only available with the `leak-check` feature.
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    pub fn assert_no_leak(mut self, assert_no_leak: bool) -> Self {
+        self.assert_no_leak = assert_no_leak;
+        self
+    }
+
+    ///
+    /// Build the pool.
+    ///
+    /// # Panics
+    /// If the elements created up front can't be created.
+    #[inline]
+    pub fn build<P>(self) -> P
+    where
+        P: TryFrom<Self, Error = PoolError>,
+    {
+        self.try_build().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Build the pool or fail if the elements created up front can't be created.
+    #[inline]
+    pub fn try_build<P>(self) -> Result<P, PoolError>
+    where
+        P: TryFrom<Self, Error = PoolError>,
+    {
+        P::try_from(self)
+    }
+
+    /// Counter of the elements limited by [`PoolBuilder::max_objects`].
+    #[inline]
+    pub(crate) fn capacity(&self) -> Capacity {
+        match self.max_objects {
+            Some(max_objects) => Capacity::new(max_objects),
+            None => Capacity::unbounded(),
+        }
+    }
+
+    /// Registry of the elements checked out of the pool.
+    #[inline]
+    pub(crate) fn leak_tracker(&self) -> LeakTracker {
+        #[cfg(feature = "leak-check")]
+        return LeakTracker::with_assert_on_drop(self.assert_no_leak);
+        #[cfg(not(feature = "leak-check"))]
+        LeakTracker::default()
+    }
+
+    /// Split the builder in `count` builders sharing the same closures, whose
+    /// limits add up to the limits of this one.
+    #[cfg(feature = "std")]
+    pub(crate) fn split(self, count: usize) -> Vec<Self>
+    where
+        T: 'static,
+    {
+        let init: Arc<dyn Fn() -> Result<T, PoolError> + Send + Sync> = Arc::from(self.init);
+        let reset: Arc<dyn Fn(&mut T) + Send + Sync> = Arc::from(self.reset);
+        let validate: Arc<dyn Fn(&T) -> bool + Send + Sync> = Arc::from(self.validate);
+        let health_check: Arc<dyn Fn(&T) -> bool + Send + Sync> = Arc::from(self.health_check);
+        let share = |total: usize, index: usize| total / count + usize::from(index < total % count);
+        (0..count)
+            .map(|index| {
+                let init = init.clone();
+                let reset = reset.clone();
+                let validate = validate.clone();
+                let health_check = health_check.clone();
+                Self {
+                    init: Box::new(move || init()),
+                    reset: Box::new(move |data| reset(data)),
+                    validate: Box::new(move |data| validate(data)),
+                    health_check: Box::new(move |data| health_check(data)),
+                    health_check_retries: self.health_check_retries,
+                    prewarm: share(self.prewarm, index),
+                    max_objects: self.max_objects.map(|max| share(max, index)),
+                    max_idle: self.max_idle.map(|max| share(max, index)),
+                    reset_mode: self.reset_mode,
+                    growth: self.growth,
+                    thread_cache: self.thread_cache,
+                    striping: self.striping,
+                    fair_lock: self.fair_lock,
+                    shards: None,
+                    waiters: self.waiters.clone(),
+                    #[cfg(feature = "leak-check")]
+                    assert_no_leak: self.assert_no_leak,
+                }
+            })
+            .collect()
+    }
+
+    /// Number of elements to create when the pool is built.
+    #[inline]
+    pub(crate) fn prewarm_count(&self) -> usize {
+        self.prewarm
+            .min(self.max_objects.unwrap_or(usize::MAX))
+            .min(self.max_idle.unwrap_or(usize::MAX))
+    }
+}
@@ -0,0 +1,151 @@
+use crate::error::PoolError;
+use crate::object_pool::ObjectPool;
+use crate::wait_queue::{WaitQueue, Waiter};
+use alloc::sync::Arc;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::panic::Location;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+/// Pool able to wake up the tasks waiting for an element.
+pub trait WaitablePool<T>: ObjectPool<T> {
+    fn waiters(&self) -> &WaitQueue;
+
+    /// Same as [`ObjectPool::try_pull_owned`], the pull being done at `location`.
+    fn try_pull_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Result<Self::OwnedReusable, PoolError>;
+}
+
+/// Future returned by the `try_pull_async` functions of the object pools.
+///
+/// It resolves to an owned element as soon as one is available. It doesn't
+/// depend on any executor: the task is woken up when an element returns in
+/// the pool. Dropping the future before its completion cancels the wait.
+///
+/// An exhausted pool is waited for, any other error of the pull ends the
+/// future with this error.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::{LinearObjectPool, PoolError};
+///  use std::sync::Arc;
+///
+///  async fn work(pool: Arc<LinearObjectPool<u32>>) -> Result<u32, PoolError> {
+///    let mut item = pool.try_pull_async().await?;
+///    *item = 5;
+///    Ok(*item * 5)
+///  }
+/// ```
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TryPullFuture<T, P: WaitablePool<T>> {
+    pool: Arc<P>,
+    waiter: Option<Arc<Waiter>>,
+    location: &'static Location<'static>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, P: WaitablePool<T>> TryPullFuture<T, P> {
+    #[inline]
+    pub(crate) fn new(pool: Arc<P>, location: &'static Location<'static>) -> Self {
+        Self {
+            pool,
+            waiter: None,
+            location,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Pull an element, none if the pool is exhausted.
+    #[inline]
+    fn try_pull(&self) -> Option<Result<P::OwnedReusable, PoolError>> {
+        match P::try_pull_owned_at(&self.pool, self.location) {
+            Err(PoolError::Exhausted) => None,
+            result => Some(result),
+        }
+    }
+}
+
+impl<T, P: WaitablePool<T>> Future for TryPullFuture<T, P> {
+    type Output = Result<P::OwnedReusable, PoolError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            if let Some(waiter) = &this.waiter {
+                if !waiter.poll_notified(cx.waker()) {
+                    return Poll::Pending;
+                }
+                this.waiter = None;
+            }
+            if let Some(result) = this.try_pull() {
+                return Poll::Ready(result);
+            }
+            let waiter = this.pool.waiters().register_waker(cx.waker());
+            // an element returned before the registration is caught by this second try
+            this.waiter = Some(waiter);
+            if let Some(result) = this.try_pull() {
+                if let Some(waiter) = this.waiter.take() {
+                    this.pool.waiters().cancel_or_forward(&waiter);
+                }
+                return Poll::Ready(result);
+            }
+        }
+    }
+}
+
+impl<T, P: WaitablePool<T>> Drop for TryPullFuture<T, P> {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter.take() {
+            self.pool.waiters().cancel_or_forward(&waiter);
+        }
+    }
+}
+
+/// Future returned by the `pull_async` functions of the object pools.
+///
+/// Same as [`TryPullFuture`], resolving to the element itself.
+///
+/// # Panics
+/// When polled, if the element can't be created or fails its health check,
+/// as the `pull` functions. Use the `try_pull_async` functions to get the
+/// error instead.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::LinearObjectPool;
+///  use std::sync::Arc;
+///
+///  async fn work(pool: Arc<LinearObjectPool<u32>>) -> u32 {
+///    let mut item = pool.pull_async().await;
+///    *item = 5;
+///    *item * 5
+///  }
+/// ```
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct PullFuture<T, P: WaitablePool<T>> {
+    inner: TryPullFuture<T, P>,
+}
+
+impl<T, P: WaitablePool<T>> PullFuture<T, P> {
+    #[inline]
+    pub(crate) fn new(pool: Arc<P>, location: &'static Location<'static>) -> Self {
+        Self {
+            inner: TryPullFuture::new(pool, location),
+        }
+    }
+}
+
+impl<T, P: WaitablePool<T>> Future for PullFuture<T, P> {
+    type Output = P::OwnedReusable;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.get_mut().inner).poll(cx) {
+            Poll::Ready(Ok(reusable)) => Poll::Ready(reusable),
+            Poll::Ready(Err(err)) => panic!("{err}"),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
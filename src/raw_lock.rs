@@ -0,0 +1,97 @@
+use crate::backoff::Backoff;
+use crate::spin_lock::{SpinLock, SpinLockGuard};
+use core::ops::DerefMut;
+#[cfg(feature = "std")]
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+///
+/// Lock over the idle elements of a [`crate::LockedObjectPool`].
+///
+/// It's implemented by [`std::sync::Mutex`] and [`SpinLock`], another lock
+/// only has to give an exclusive access to its data through a guard.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::{LockedObjectPool, PoolBuilder, RawLock};
+///  use std::sync::{RwLock, RwLockWriteGuard};
+///
+///  struct WriteLock<T>(RwLock<T>);
+///
+///  impl<T> RawLock<T> for WriteLock<T> {
+///    type Guard<'a> = RwLockWriteGuard<'a, T> where Self: 'a;
+///
+///    fn new(data: T) -> Self {
+///      Self(RwLock::new(data))
+///    }
+///
+///    fn lock(&self) -> Self::Guard<'_> {
+///      self.0.write().unwrap()
+///    }
+///  }
+///
+///  let pool: LockedObjectPool<u32, WriteLock<Vec<u32>>> = PoolBuilder::new(|| 0).build();
+///  let mut item = pool.pull();
+///  *item = 5;
+/// ```
+pub trait RawLock<T>: Sized {
+    /// Exclusive access to the data, the lock is released when it's dropped.
+    type Guard<'a>: DerefMut<Target = T>
+    where
+        Self: 'a;
+
+    /// Create an unlocked lock over `data`.
+    fn new(data: T) -> Self;
+
+    /// Create an unlocked lock over `data`, granted in the order of the requests.
+    ///
+    /// Used when [`crate::PoolBuilder::fair_lock`] is set, the lock created by
+    /// [`RawLock::new`] by default.
+    #[inline]
+    fn new_fair(data: T) -> Self {
+        Self::new(data)
+    }
+
+    /// Wait for the lock.
+    fn lock(&self) -> Self::Guard<'_>;
+}
+
+#[cfg(feature = "std")]
+impl<T> RawLock<T> for Mutex<T> {
+    type Guard<'a>
+        = MutexGuard<'a, T>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn new(data: T) -> Self {
+        Mutex::new(data)
+    }
+
+    #[inline]
+    fn lock(&self) -> Self::Guard<'_> {
+        // the data of a pool is consistent even if a thread panicked while holding the lock
+        Mutex::lock(self).unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T, B: Backoff> RawLock<T> for SpinLock<T, B> {
+    type Guard<'a>
+        = SpinLockGuard<'a, T, B>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn new(data: T) -> Self {
+        SpinLock::new(data)
+    }
+
+    #[inline]
+    fn new_fair(data: T) -> Self {
+        SpinLock::new_fair(data)
+    }
+
+    #[inline]
+    fn lock(&self) -> Self::Guard<'_> {
+        SpinLock::lock(self)
+    }
+}
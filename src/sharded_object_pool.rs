@@ -0,0 +1,463 @@
+#[cfg(feature = "leak-check")]
+use crate::leak_check::Outstanding;
+#[cfg(feature = "stats")]
+use crate::stats::PoolStats;
+use crate::{
+    error::PoolError,
+    locked_object_pool::{LockedObjectPool, SpinLockObjectPool},
+    object_pool::ObjectPool,
+    pool_builder::PoolBuilder,
+    pull_future::{PullFuture, TryPullFuture, WaitablePool},
+    raw_lock::RawLock,
+    thread_index,
+    wait_queue::WaitQueue,
+};
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+use core::panic::Location;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Pool usable as a shard of a [`ShardedObjectPool`], it's implemented by
+/// [`LockedObjectPool`], so by [`crate::MutexObjectPool`] and [`SpinLockObjectPool`].
+pub trait Shard<T>:
+    WaitablePool<T> + TryFrom<PoolBuilder<T>, Error = PoolError> + sealed::Sealed
+{
+    /// Pull an idle element without calling `init`, none if there isn't any.
+    #[doc(hidden)]
+    #[track_caller]
+    fn try_pull_idle(&self) -> Option<Result<Self::Reusable<'_>, PoolError>>;
+
+    /// Pull an idle element without calling `init`, none if there isn't any.
+    #[doc(hidden)]
+    fn try_pull_idle_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Option<Result<Self::OwnedReusable, PoolError>>;
+}
+
+impl<T, L: RawLock<Vec<T>>> sealed::Sealed for LockedObjectPool<T, L> {}
+
+impl<T, L: RawLock<Vec<T>>> Shard<T> for LockedObjectPool<T, L> {
+    #[inline]
+    #[track_caller]
+    fn try_pull_idle(&self) -> Option<Result<Self::Reusable<'_>, PoolError>> {
+        LockedObjectPool::try_pull_idle(self)
+    }
+
+    #[inline]
+    fn try_pull_idle_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Option<Result<Self::OwnedReusable, PoolError>> {
+        LockedObjectPool::try_pull_idle_owned_at(self, location)
+    }
+}
+
+/// ObjectPool split in several shards to reduce the contention on their lock.
+///
+/// Each thread pulls from the shard of its index. When this shard has no idle
+/// element, one is stolen from the next shards before `init` is called. An
+/// element returns in the shard it has been pulled from.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::ShardedObjectPool;
+///
+///  let pool = ShardedObjectPool::<u32>::new(
+///    ||  Default::default(),
+///    |v| {
+///      *v = 0;
+///    }
+///  );
+///  let mut item = pool.pull();
+///
+///  *item = 5;
+///  let work = *item * 5;
+/// ```
+pub struct ShardedObjectPool<T, P = SpinLockObjectPool<T>> {
+    shards: Box<[Arc<P>]>,
+    /// Wait queue of all the shards, an element returned in any shard wakes up a waiter.
+    waiters: Arc<WaitQueue>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, P: Shard<T>> ShardedObjectPool<T, P> {
+    ///
+    /// Create an new [`ShardedObjectPool`] with a shard per CPU.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{MutexObjectPool, ShardedObjectPool};
+    ///
+    ///  let pool = ShardedObjectPool::<u32, MutexObjectPool<u32>>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    /// ```
+    #[inline]
+    pub fn new<R, I>(init: I, reset: R) -> Self
+    where
+        T: 'static,
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> T + Send + Sync + 'static,
+    {
+        PoolBuilder::new(init).reset(reset).build()
+    }
+
+    ///
+    /// Create an new [`ShardedObjectPool`] with at most `max_objects` elements alive,
+    /// split between its shards.
+    ///
+    /// When the limit is reached, [`ShardedObjectPool::try_pull`] fails and
+    /// [`ShardedObjectPool::pull`] panics.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    /// * `max_objects` maximum number of elements alive
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::ShardedObjectPool;
+    ///
+    ///  let pool = ShardedObjectPool::<u32>::new_bounded(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    },
+    ///    1
+    ///  );
+    ///  let item = pool.pull();
+    ///  assert!(pool.try_pull().is_err());
+    /// ```
+    #[inline]
+    pub fn new_bounded<R, I>(init: I, reset: R, max_objects: usize) -> Self
+    where
+        T: 'static,
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> T + Send + Sync + 'static,
+    {
+        PoolBuilder::new(init)
+            .reset(reset)
+            .max_objects(max_objects)
+            .build()
+    }
+
+    ///
+    /// Create a new element. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::ShardedObjectPool;
+    ///
+    ///  let pool = ShardedObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let mut item = pool.pull();
+    /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are alive.
+    #[inline]
+    #[track_caller]
+    pub fn pull(&self) -> P::Reusable<'_> {
+        self.try_pull().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::ShardedObjectPool;
+    ///
+    ///  let pool = ShardedObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let mut item = pool.try_pull().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull(&self) -> Result<P::Reusable<'_>, PoolError> {
+        let start = self.local();
+        for shard in self.neighbours(start) {
+            if let Some(item) = shard.try_pull_idle() {
+                return item;
+            }
+        }
+        for shard in self.neighbours(start) {
+            match shard.try_pull() {
+                Err(PoolError::Exhausted) => {}
+                item => return item,
+            }
+        }
+        Err(PoolError::Exhausted)
+    }
+
+    ///
+    /// Create a new element. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::ShardedObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(ShardedObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let mut item = pool.pull_owned();
+    /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are alive.
+    #[inline]
+    #[track_caller]
+    pub fn pull_owned(self: &Arc<Self>) -> P::OwnedReusable {
+        self.try_pull_owned().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it returns in the pull.
+    ///
+    /// The element only keeps its shard alive, not the whole pool.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::ShardedObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(ShardedObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let mut item = pool.try_pull_owned().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_owned(self: &Arc<Self>) -> Result<P::OwnedReusable, PoolError> {
+        self.try_pull_owned_at(Location::caller())
+    }
+
+    #[inline]
+    fn try_pull_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Result<P::OwnedReusable, PoolError> {
+        let start = self.local();
+        for shard in self.neighbours(start) {
+            if let Some(item) = P::try_pull_idle_owned_at(shard, location) {
+                return item;
+            }
+        }
+        for shard in self.neighbours(start) {
+            match P::try_pull_owned_at(shard, location) {
+                Err(PoolError::Exhausted) => {}
+                item => return item,
+            }
+        }
+        Err(PoolError::Exhausted)
+    }
+
+    ///
+    /// Create a new element, waiting asynchronously for an element to be returned in any shard
+    /// if the pool is exhausted. When the element is dropped, it returns in the pull.
+    ///
+    /// The returned future doesn't depend on any executor, an unbounded pool never waits.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::ShardedObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  async fn work(pool: Arc<ShardedObjectPool<u32>>) {
+    ///    let mut item = pool.pull_async().await;
+    ///    *item = 5;
+    ///  }
+    /// ```
+    ///
+    /// # Panics
+    /// When polled, if the element can't be created or fails its health check.
+    #[inline]
+    #[track_caller]
+    pub fn pull_async(self: &Arc<Self>) -> PullFuture<T, Self> {
+        PullFuture::new(self.clone(), Location::caller())
+    }
+
+    ///
+    /// Same as `pull_async`, the future resolving to the error if the element
+    /// can't be created or fails its health check.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{PoolError, ShardedObjectPool};
+    ///  use std::sync::Arc;
+    ///
+    ///  async fn work(pool: Arc<ShardedObjectPool<u32>>) -> Result<(), PoolError> {
+    ///    let mut item = pool.try_pull_async().await?;
+    ///    *item = 5;
+    ///    Ok(())
+    ///  }
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_async(self: &Arc<Self>) -> TryPullFuture<T, Self> {
+        TryPullFuture::new(self.clone(), Location::caller())
+    }
+
+    ///
+    /// Number of shards of the pool.
+    #[inline]
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    ///
+    /// Elements still checked out with the call site of their pull, the oldest first.
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    pub fn outstanding(&self) -> Vec<Outstanding> {
+        let mut outstanding: Vec<_> = self
+            .shards
+            .iter()
+            .flat_map(|shard| shard.outstanding())
+            .collect();
+        outstanding.sort_by_key(|item| item.id);
+        outstanding
+    }
+
+    ///
+    /// Sum of the statistics of the shards.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> PoolStats {
+        self.shards
+            .iter()
+            .map(|shard| shard.stats())
+            .fold(PoolStats::default(), |total, stats| PoolStats {
+                pulls: total.pulls + stats.pulls,
+                hits: total.hits + stats.hits,
+                inits: total.inits + stats.inits,
+                returns: total.returns + stats.returns,
+                in_use: total.in_use + stats.in_use,
+                idle: total.idle + stats.idle,
+                pages: total.pages + stats.pages,
+            })
+    }
+
+    /// Index of the shard of the current thread.
+    #[inline]
+    fn local(&self) -> usize {
+        thread_index::current().map_or(0, |thread| thread.index) % self.shards.len()
+    }
+
+    /// All the shards, starting from `start`.
+    #[inline]
+    fn neighbours(&self, start: usize) -> impl Iterator<Item = &Arc<P>> {
+        self.shards[start..].iter().chain(&self.shards[..start])
+    }
+}
+
+impl<T, P: Shard<T>> ObjectPool<T> for ShardedObjectPool<T, P> {
+    type Reusable<'a>
+        = P::Reusable<'a>
+    where
+        Self: 'a;
+    type OwnedReusable = P::OwnedReusable;
+
+    #[inline]
+    #[track_caller]
+    fn pull(&self) -> Self::Reusable<'_> {
+        ShardedObjectPool::pull(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn pull_owned(self: &Arc<Self>) -> Self::OwnedReusable {
+        ShardedObjectPool::pull_owned(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn try_pull(&self) -> Result<Self::Reusable<'_>, PoolError> {
+        ShardedObjectPool::try_pull(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn try_pull_owned(self: &Arc<Self>) -> Result<Self::OwnedReusable, PoolError> {
+        ShardedObjectPool::try_pull_owned(self)
+    }
+
+    #[cfg(feature = "stats")]
+    #[inline]
+    fn stats(&self) -> PoolStats {
+        ShardedObjectPool::stats(self)
+    }
+
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    fn outstanding(&self) -> Vec<Outstanding> {
+        ShardedObjectPool::outstanding(self)
+    }
+}
+
+impl<T, P: Shard<T>> WaitablePool<T> for ShardedObjectPool<T, P> {
+    #[inline]
+    fn waiters(&self) -> &WaitQueue {
+        &self.waiters
+    }
+
+    #[inline]
+    fn try_pull_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Result<Self::OwnedReusable, PoolError> {
+        ShardedObjectPool::try_pull_owned_at(self, location)
+    }
+}
+
+/// `max_objects`, `max_idle` and `prewarm` are split between the shards, a
+/// bounded pool has at most `max_objects` shards so that each one holds an element.
+impl<T: 'static, P: Shard<T>> TryFrom<PoolBuilder<T>> for ShardedObjectPool<T, P> {
+    type Error = PoolError;
+
+    #[inline]
+    fn try_from(mut builder: PoolBuilder<T>) -> Result<Self, Self::Error> {
+        let waiters = Arc::new(WaitQueue::new());
+        builder.waiters = Some(waiters.clone());
+        let count = builder
+            .shards
+            .unwrap_or_else(thread_index::available_parallelism)
+            .min(builder.max_objects.unwrap_or(usize::MAX).max(1));
+        let shards = builder
+            .split(count)
+            .into_iter()
+            .map(|builder| P::try_from(builder).map(Arc::new))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            shards,
+            waiters,
+            _marker: PhantomData,
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use crate::leak_check::Ticket;
+use crate::linear_object_pool::LinearObjectPool;
+use crate::linear_page::LinearPage;
+use crate::page::PageId;
+use alloc::sync::Arc;
+use core::ops::Deref;
+
+/// Shared wrapper over T used by [`LinearObjectPool`].
+///
+/// It's cloned like an [`Arc`] without allocation, the reference count is
+/// stored in the page next to the element. The element returns in the pool
+/// when the last clone is dropped.
+///
+/// Access is allowed with [`std::ops::Deref`]
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::LinearObjectPool;
+///  use std::sync::Arc;
+///  use std::thread;
+///
+///  let pool = Arc::new(LinearObjectPool::<u32>::new(
+///    ||  Default::default(),
+///    |v| {
+///      *v = 0;
+///    }
+///  ));
+///  let mut item = pool.pull_owned();
+///  *item = 5;
+///  let item = item.share();
+///
+///  let children: Vec<_> = (0..4)
+///    .map(|_| {
+///      let item = item.clone();
+///      thread::spawn(move || *item * 5)
+///    })
+///    .collect();
+///  for child in children {
+///    assert_eq!(child.join().unwrap(), 25);
+///  }
+/// ```
+pub struct SharedReusable<T, const N: usize = 32> {
+    pool: Arc<LinearObjectPool<T, N>>,
+    page_id: PageId,
+    page: *const LinearPage<T, N>,
+    ticket: Ticket,
+}
+
+impl<T, const N: usize> SharedReusable<T, N> {
+    /// Create new element with a single reference
+    ///
+    /// # Arguments
+    /// * `pool` object pool owner
+    /// * `page_id` page id
+    /// * `page`    page that contains data
+    /// * `ticket`  leak check ticket of the pull
+    /// # Safety
+    /// * `page` has to be a valid pointer to a page in `pool`
+    /// * `pool_id` has to be a valid id for `page`
+    /// * nobody else can use the element of `page_id`
+    #[inline]
+    pub(crate) unsafe fn new(
+        pool: Arc<LinearObjectPool<T, N>>,
+        page_id: PageId,
+        page: &LinearPage<T, N>,
+        ticket: Ticket,
+    ) -> Self {
+        page.share(&page_id);
+        Self {
+            pool,
+            page_id,
+            page,
+            ticket,
+        }
+    }
+}
+
+impl<T, const N: usize> Clone for SharedReusable<T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        unsafe {
+            // SAFETY: the page lives as long as the pool
+            self.page.as_ref().unwrap().add_ref(&self.page_id);
+        }
+        Self {
+            pool: self.pool.clone(),
+            page_id: self.page_id,
+            page: self.page,
+            ticket: self.ticket.clone(),
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for SharedReusable<T, N> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe {
+            // SAFETY: the element is only read while it's shared
+            self.page.as_ref().unwrap().get(&self.page_id)
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for SharedReusable<T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            // SAFETY: the page lives as long as the pool
+            let page = self.page.as_ref().unwrap();
+            if page.remove_ref(&self.page_id) {
+                // SAFETY: this is the last reference to the element
+                self.pool.recycle(page, &self.page_id, &self.ticket);
+            }
+        }
+    }
+}
+
+unsafe impl<T: Send + Sync, const N: usize> Send for SharedReusable<T, N> {} // SAFETY: as Arc, the element is read and dropped from any thread
+unsafe impl<T: Send + Sync, const N: usize> Sync for SharedReusable<T, N> {} // SAFETY: as Arc, the element is read and dropped from any thread
@@ -1,62 +1,115 @@
-use std::cell::UnsafeCell;
-use std::ops::{Deref, DerefMut};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::thread;
+use crate::backoff::{Backoff, YieldBackoff};
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
-pub struct SpinLock<T> {
+enum State {
+    /// Taken by the first thread seeing it free.
+    Unfair(AtomicBool),
+    /// Each thread takes a ticket and waits for its turn, in the order of the tickets.
+    Ticket { next: AtomicU32, serving: AtomicU32 },
+}
+
+///
+/// Lock waiting actively with the [`Backoff`] strategy `B`, used by [`crate::SpinLockObjectPool`].
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::SpinLock;
+///
+///  let lock = SpinLock::<u32>::new(0);
+///  *lock.lock() += 1;
+///  assert_eq!(*lock.lock(), 1);
+/// ```
+pub struct SpinLock<T, B = YieldBackoff> {
     data: UnsafeCell<T>,
-    lock: AtomicBool,
+    state: State,
+    backoff: PhantomData<fn() -> B>,
 }
 
-impl<T> SpinLock<T> {
+impl<T, B: Backoff> SpinLock<T, B> {
+    /// Create an unfair lock, taken by the first thread seeing it free.
     #[inline]
     pub fn new(data: T) -> Self {
+        Self::with_state(data, State::Unfair(AtomicBool::new(false)))
+    }
+
+    /// Create a lock granted in the order of the requests.
+    #[inline]
+    pub fn new_fair(data: T) -> Self {
+        Self::with_state(
+            data,
+            State::Ticket {
+                next: AtomicU32::new(0),
+                serving: AtomicU32::new(0),
+            },
+        )
+    }
+
+    #[inline]
+    fn with_state(data: T, state: State) -> Self {
         Self {
             data: UnsafeCell::new(data),
-            lock: AtomicBool::new(false),
+            state,
+            backoff: PhantomData,
         }
     }
 
+    /// Wait for the lock, it's released when the guard is dropped.
     #[inline]
-    pub fn lock(&self) -> SpinLockGuard<T> {
+    pub fn lock(&self) -> SpinLockGuard<'_, T, B> {
         self.acquire();
         SpinLockGuard { lock: self }
     }
 
     #[inline]
     fn acquire(&self) {
-        self.exchange(false, true);
+        let mut backoff = B::default();
+        match &self.state {
+            State::Unfair(lock) => {
+                while lock
+                    .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_err()
+                {
+                    // test and test and set, a load doesn't take the cache line from the holder
+                    while lock.load(Ordering::Relaxed) {
+                        backoff.snooze();
+                    }
+                }
+            }
+            State::Ticket { next, serving } => {
+                // the tickets wrap around, there can't be 2^32 waiting threads
+                let ticket = next.fetch_add(1, Ordering::Relaxed);
+                while serving.load(Ordering::Acquire) != ticket {
+                    backoff.snooze();
+                }
+            }
+        }
     }
 
     #[inline]
     fn release(&self) {
-        self.exchange(true, false);
-    }
-
-    #[inline]
-    fn exchange(&self, from: bool, to: bool) {
-        loop {
-            match self
-                .lock
-                .compare_exchange_weak(from, to, Ordering::SeqCst, Ordering::Relaxed)
-            {
-                Ok(_) => break,
-                Err(_) => {
-                    thread::yield_now();
-                }
+        match &self.state {
+            State::Unfair(lock) => lock.store(false, Ordering::Release),
+            State::Ticket { serving, .. } => {
+                // only the holder updates serving
+                let ticket = serving.load(Ordering::Relaxed);
+                serving.store(ticket.wrapping_add(1), Ordering::Release);
             }
         }
     }
 }
 
-unsafe impl<T: Send> Send for SpinLock<T> {} // SAFETY: sending the data is allowed if it's Send
-unsafe impl<T: Send> Sync for SpinLock<T> {} // SAFETY: the Mutex manages synchronization so only Send is required
+unsafe impl<T: Send, B> Send for SpinLock<T, B> {} // SAFETY: sending the data is allowed if it's Send
+unsafe impl<T: Send, B> Sync for SpinLock<T, B> {} // SAFETY: the Mutex manages synchronization so only Send is required
 
-pub struct SpinLockGuard<'a, T> {
-    lock: &'a SpinLock<T>,
+/// Exclusive access to the data of a [`SpinLock`].
+pub struct SpinLockGuard<'a, T, B: Backoff> {
+    lock: &'a SpinLock<T, B>,
 }
 
-impl<T> DerefMut for SpinLockGuard<'_, T> {
+impl<T, B: Backoff> DerefMut for SpinLockGuard<'_, T, B> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         unsafe {
@@ -66,7 +119,7 @@ impl<T> DerefMut for SpinLockGuard<'_, T> {
     }
 }
 
-impl<T> Deref for SpinLockGuard<'_, T> {
+impl<T, B: Backoff> Deref for SpinLockGuard<'_, T, B> {
     type Target = T;
 
     #[inline]
@@ -78,12 +131,12 @@ impl<T> Deref for SpinLockGuard<'_, T> {
     }
 }
 
-impl<T> Drop for SpinLockGuard<'_, T> {
+impl<T, B: Backoff> Drop for SpinLockGuard<'_, T, B> {
     #[inline]
     fn drop(&mut self) {
         self.lock.release();
     }
 }
 
-unsafe impl<T: Send> Send for SpinLockGuard<'_, T> {} // SAFETY: normal rules apply
-unsafe impl<T: Sync> Sync for SpinLockGuard<'_, T> {} // SAFETY: normal rules apply
+unsafe impl<T: Send, B: Backoff> Send for SpinLockGuard<'_, T, B> {} // SAFETY: normal rules apply
+unsafe impl<T: Sync, B: Backoff> Sync for SpinLockGuard<'_, T, B> {} // SAFETY: normal rules apply
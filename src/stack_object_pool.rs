@@ -0,0 +1,674 @@
+#[cfg(feature = "leak-check")]
+use crate::leak_check::Outstanding;
+#[cfg(feature = "stats")]
+use crate::stats::PoolStats;
+use crate::{
+    capacity::Capacity,
+    error::PoolError,
+    leak_check::{LeakTracker, Ticket},
+    node_stack::{NodeId, NodeStack},
+    object_pool::ObjectPool,
+    pool_builder::{Growth, PoolBuilder, ResetMode},
+    pull_future::{PullFuture, TryPullFuture, WaitablePool},
+    stack_owned_reusable::StackOwnedReusable,
+    stack_reusable::StackReusable,
+    stats::Stats,
+    wait_queue::WaitQueue,
+};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::error::Error;
+use core::panic::Location;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// ObjectPool use a lockfree stack of idle elements to secure multithread access to pull.
+///
+/// The stack is a linked list of nodes whose head is tagged by a generation
+/// to avoid the ABA problem. A pull and a return don't depend on the number
+/// of elements, and the last returned element is the first reused while it's
+/// still in the cache.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::StackObjectPool;
+///
+///  let pool = StackObjectPool::<u32>::new(
+///    ||  Default::default(),
+///    |v| {
+///      *v = 0;
+///    }
+///  );
+///  let mut item = pool.pull();
+///
+///  *item = 5;
+///  let work = *item * 5;
+/// ```
+pub struct StackObjectPool<T> {
+    nodes: NodeStack<T>,
+    reset: Box<dyn Fn(&mut T) + Send + Sync>,
+    validate: Box<dyn Fn(&T) -> bool + Send + Sync>,
+    init: Box<dyn Fn() -> Result<T, PoolError> + Send + Sync>,
+    health_check: Box<dyn Fn(&T) -> bool + Send + Sync>,
+    health_check_retries: usize,
+    capacity: Capacity,
+    reset_mode: ResetMode,
+    growth: Growth,
+    idle: AtomicUsize,
+    max_idle: usize,
+    waiters: WaitQueue,
+    stats: Stats,
+    tracker: LeakTracker,
+}
+
+impl<T> StackObjectPool<T> {
+    ///
+    /// Create an new [`StackObjectPool`]
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::StackObjectPool;
+    ///
+    ///  let pool = StackObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    /// ```
+    #[inline]
+    pub fn new<R, I>(init: I, reset: R) -> Self
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> T + Send + Sync + 'static,
+    {
+        PoolBuilder::new(init).reset(reset).build()
+    }
+
+    ///
+    /// Create an new [`StackObjectPool`] with at most `max_objects` elements.
+    ///
+    /// When the limit is reached, [`StackObjectPool::try_pull`] fails and
+    /// [`StackObjectPool::pull`] panics.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    /// * `max_objects` maximum number of elements
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::StackObjectPool;
+    ///
+    ///  let pool = StackObjectPool::<u32>::new_bounded(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    },
+    ///    1
+    ///  );
+    ///  let item = pool.pull();
+    ///  assert!(pool.try_pull().is_err());
+    /// ```
+    #[inline]
+    pub fn new_bounded<R, I>(init: I, reset: R, max_objects: usize) -> Self
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> T + Send + Sync + 'static,
+    {
+        PoolBuilder::new(init)
+            .reset(reset)
+            .max_objects(max_objects)
+            .build()
+    }
+
+    ///
+    /// Create an new [`StackObjectPool`] whose elements creation can fail.
+    ///
+    /// The error of `init` is returned as [`PoolError::Init`] by the `try_pull` functions.
+    ///
+    /// # Arguments
+    /// * `init`  closure to create new item
+    /// * `reset` closure to reset item before reusage
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{StackObjectPool, PoolError};
+    ///
+    ///  let pool = StackObjectPool::<u32>::try_new(
+    ///    || "x".parse::<u32>(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ).unwrap();
+    ///  assert!(matches!(pool.try_pull(), Err(PoolError::Init(_))));
+    /// ```
+    #[inline]
+    pub fn try_new<R, I, E>(init: I, reset: R) -> Result<Self, PoolError>
+    where
+        R: Fn(&mut T) + Send + Sync + 'static,
+        I: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        PoolBuilder::try_new(init).reset(reset).try_build()
+    }
+
+    ///
+    /// Create a new element. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::StackObjectPool;
+    ///
+    ///  let pool = StackObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let mut item = pool.pull();
+    /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are in use.
+    #[inline]
+    #[track_caller]
+    pub fn pull(&self) -> StackReusable<'_, T> {
+        self.try_pull().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::StackObjectPool;
+    ///
+    ///  let pool = StackObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let mut item = pool.try_pull().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull(&self) -> Result<StackReusable<'_, T>, PoolError> {
+        let id = self.take()?;
+        let ticket = self.tracker.track(Location::caller());
+        Ok(unsafe { StackReusable::new(self, id, ticket) })
+    }
+
+    ///
+    /// Create a new element. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::StackObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(StackObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let mut item = pool.pull_owned();
+    /// ```
+    ///
+    /// # Panics
+    /// If the pool is bounded and all its elements are in use.
+    #[inline]
+    #[track_caller]
+    pub fn pull_owned(self: &Arc<Self>) -> StackOwnedReusable<T> {
+        self.try_pull_owned().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    ///
+    /// Create a new element or fail if the pool is exhausted. When the element is dropped, it returns in the pull.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::StackObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  let pool = Arc::new(StackObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  ));
+    ///  let mut item = pool.try_pull_owned().unwrap();
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_owned(self: &Arc<Self>) -> Result<StackOwnedReusable<T>, PoolError> {
+        self.try_pull_owned_at(Location::caller())
+    }
+
+    #[inline]
+    fn try_pull_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Result<StackOwnedReusable<T>, PoolError> {
+        let id = self.take()?;
+        let ticket = self.tracker.track(location);
+        Ok(unsafe { StackOwnedReusable::new(self.clone(), id, ticket) })
+    }
+
+    ///
+    /// Create a new element, waiting asynchronously for an element to be returned if the pool is exhausted.
+    /// When the element is dropped, it returns in the pull.
+    ///
+    /// The returned future doesn't depend on any executor, an unbounded pool never waits.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::StackObjectPool;
+    ///  use std::sync::Arc;
+    ///
+    ///  async fn work(pool: Arc<StackObjectPool<u32>>) {
+    ///    let mut item = pool.pull_async().await;
+    ///    *item = 5;
+    ///  }
+    /// ```
+    ///
+    /// # Panics
+    /// When polled, if the element can't be created or fails its health check.
+    #[inline]
+    #[track_caller]
+    pub fn pull_async(self: &Arc<Self>) -> PullFuture<T, Self> {
+        PullFuture::new(self.clone(), Location::caller())
+    }
+
+    ///
+    /// Same as `pull_async`, the future resolving to the error if the element
+    /// can't be created or fails its health check.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{StackObjectPool, PoolError};
+    ///  use std::sync::Arc;
+    ///
+    ///  async fn work(pool: Arc<StackObjectPool<u32>>) -> Result<(), PoolError> {
+    ///    let mut item = pool.try_pull_async().await?;
+    ///    *item = 5;
+    ///    Ok(())
+    ///  }
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn try_pull_async(self: &Arc<Self>) -> TryPullFuture<T, Self> {
+        TryPullFuture::new(self.clone(), Location::caller())
+    }
+
+    ///
+    /// Give an element to the pool. It will be reset and reused by a next pull.
+    ///
+    /// # Arguments
+    /// * `data` element to add, it can have been created outside of the pool
+    ///   or detached from it. If the pool is bounded and full, it is dropped.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::StackObjectPool;
+    ///
+    ///  let pool = StackObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  pool.attach(5);
+    ///  let item = pool.pull();
+    ///  assert_eq!(*item, 0);
+    /// ```
+    #[inline]
+    pub fn attach(&self, data: T) {
+        if self.capacity.acquire() {
+            let id = self.nodes.insert(data);
+            unsafe {
+                // SAFETY: id has just been inserted so nobody else uses it
+                self.store(id);
+            }
+        }
+    }
+
+    ///
+    /// Drop the idle elements above `min_idle`.
+    ///
+    /// The nodes of the dropped elements are kept to hold the next elements.
+    ///
+    /// # Arguments
+    /// * `min_idle` number of idle elements to keep
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::StackObjectPool;
+    ///
+    ///  let pool = StackObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let items: Vec<_> = (0..100).map(|_| pool.pull()).collect();
+    ///  drop(items);
+    ///  pool.shrink_to(10);
+    /// ```
+    #[inline]
+    pub fn shrink_to(&self, min_idle: usize) {
+        while self.idle.load(Ordering::Relaxed) > min_idle {
+            let Some(id) = self.pop_idle() else {
+                return;
+            };
+            unsafe {
+                // SAFETY: id has just been popped so nobody else uses it
+                self.nodes.remove(id);
+            }
+            self.release();
+        }
+    }
+
+    ///
+    /// Drop all the idle elements.
+    ///
+    /// cf [`StackObjectPool::shrink_to`]
+    #[inline]
+    pub fn trim(&self) {
+        self.shrink_to(0);
+    }
+
+    ///
+    /// Elements still checked out with the call site of their pull, the oldest first.
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    pub fn outstanding(&self) -> Vec<Outstanding> {
+        self.tracker.outstanding()
+    }
+
+    ///
+    /// Snapshot of the statistics of the pool, `pages` is always 0.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn stats(&self) -> PoolStats {
+        self.stats.snapshot(self.idle.load(Ordering::Relaxed), 0)
+    }
+
+    #[inline]
+    fn take(&self) -> Result<NodeId, PoolError> {
+        match self.pop_idle() {
+            Some(id) => self.reuse(id),
+            None if self.capacity.acquire() => self.create(),
+            None => Err(PoolError::Exhausted),
+        }
+    }
+
+    #[inline]
+    fn pop_idle(&self) -> Option<NodeId> {
+        let id = self.nodes.pop_idle()?;
+        self.idle.fetch_sub(1, Ordering::Relaxed);
+        Some(id)
+    }
+
+    /// # Safety
+    /// * `id` has to hold an element not used anymore
+    #[inline]
+    pub(crate) unsafe fn get(&self, id: NodeId) -> &T {
+        self.nodes.get(id)
+    }
+
+    /// # Safety
+    /// * `id` has to hold an element not used anymore
+    #[inline]
+    #[allow(clippy::mut_from_ref)] // the function is marked as unsafe for a reason
+    pub(crate) unsafe fn get_mut(&self, id: NodeId) -> &mut T {
+        self.nodes.get_mut(id)
+    }
+
+    /// Return an element in the pool.
+    ///
+    /// # Safety
+    /// * `id` has to hold an element not used anymore
+    #[inline]
+    pub(crate) unsafe fn recycle(&self, id: NodeId, ticket: &Ticket) {
+        self.tracker.untrack(ticket);
+        self.stats.on_return();
+        self.store(id);
+    }
+
+    /// # Safety
+    /// * `id` has to hold an element not used anymore
+    #[inline]
+    unsafe fn store(&self, id: NodeId) {
+        let data = self.nodes.get_mut(id);
+        if !(self.validate)(data) {
+            self.nodes.remove(id);
+            self.release();
+            return;
+        }
+        if self.reset_mode == ResetMode::OnReturn {
+            (self.reset)(data);
+        }
+        self.push(id);
+    }
+
+    /// # Safety
+    /// * `id` has to hold an element not used anymore
+    #[inline]
+    unsafe fn push(&self, id: NodeId) {
+        let max_idle = self.max_idle;
+        if self
+            .idle
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |idle| {
+                (idle < max_idle).then_some(idle + 1)
+            })
+            .is_err()
+        {
+            self.nodes.remove(id);
+            self.release();
+            return;
+        }
+        self.nodes.push_idle(id);
+        if self.capacity.is_bounded() {
+            self.waiters.notify_one();
+        }
+    }
+
+    /// Take the element out of the pool, its node is kept for a next element.
+    ///
+    /// # Safety
+    /// * `id` has to hold an element not used anymore
+    #[inline]
+    pub(crate) unsafe fn on_detach(&self, id: NodeId, ticket: &Ticket) -> T {
+        self.tracker.untrack(ticket);
+        self.stats.on_detach();
+        let data = self.nodes.take(id);
+        self.release();
+        data
+    }
+
+    /// Prepare an idle element before its reusage, its capacity is released on failure.
+    #[inline]
+    fn reuse(&self, id: NodeId) -> Result<NodeId, PoolError> {
+        if self.reset_mode == ResetMode::OnPull {
+            (self.reset)(unsafe {
+                // SAFETY: id has just been popped so nobody else uses it
+                self.nodes.get_mut(id)
+            });
+        }
+        self.check(id).inspect(|_| self.stats.on_pull(true))
+    }
+
+    /// Create an element, its capacity has already been acquired and is released on failure.
+    #[inline]
+    fn create(&self) -> Result<NodeId, PoolError> {
+        match self
+            .grow(self.growth.batch_size() - 1)
+            .and_then(|_| self.call_init())
+        {
+            Ok(data) => self
+                .check(self.nodes.insert(data))
+                .inspect(|_| self.stats.on_pull(false)),
+            Err(err) => {
+                self.release();
+                Err(err)
+            }
+        }
+    }
+
+    /// Replace an element failing the health check by new ones, its capacity is released on failure.
+    #[inline]
+    fn check(&self, id: NodeId) -> Result<NodeId, PoolError> {
+        let data = unsafe {
+            // SAFETY: id has just been allocated so nobody else uses it
+            self.nodes.get_mut(id)
+        };
+        let mut retries = self.health_check_retries;
+        while !(self.health_check)(data) {
+            if retries == 0 {
+                unsafe { self.nodes.remove(id) };
+                self.release();
+                return Err(PoolError::Unhealthy);
+            }
+            retries -= 1;
+            match self.call_init() {
+                Ok(new) => *data = new,
+                Err(err) => {
+                    unsafe { self.nodes.remove(id) };
+                    self.release();
+                    return Err(err);
+                }
+            }
+        }
+        Ok(id)
+    }
+
+    #[inline]
+    fn call_init(&self) -> Result<T, PoolError> {
+        self.stats.on_init();
+        (self.init)()
+    }
+
+    /// Add at most `count` idle elements, stopping at the first failure.
+    #[inline]
+    fn grow(&self, count: usize) -> Result<(), PoolError> {
+        for _ in 0..count {
+            if !self.capacity.acquire() {
+                break;
+            }
+            match self.call_init() {
+                Ok(data) => unsafe {
+                    // SAFETY: the node has just been inserted so nobody else uses it
+                    self.push(self.nodes.insert(data))
+                },
+                Err(err) => {
+                    self.release();
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Release the capacity of a dropped element.
+    #[inline]
+    fn release(&self) {
+        if self.capacity.is_bounded() {
+            self.capacity.release();
+            self.waiters.notify_one();
+        }
+    }
+}
+
+impl<T> ObjectPool<T> for StackObjectPool<T> {
+    type Reusable<'a>
+        = StackReusable<'a, T>
+    where
+        Self: 'a;
+    type OwnedReusable = StackOwnedReusable<T>;
+
+    #[inline]
+    #[track_caller]
+    fn pull(&self) -> Self::Reusable<'_> {
+        StackObjectPool::pull(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn pull_owned(self: &Arc<Self>) -> Self::OwnedReusable {
+        StackObjectPool::pull_owned(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn try_pull(&self) -> Result<Self::Reusable<'_>, PoolError> {
+        StackObjectPool::try_pull(self)
+    }
+
+    #[inline]
+    #[track_caller]
+    fn try_pull_owned(self: &Arc<Self>) -> Result<Self::OwnedReusable, PoolError> {
+        StackObjectPool::try_pull_owned(self)
+    }
+
+    #[cfg(feature = "stats")]
+    #[inline]
+    fn stats(&self) -> PoolStats {
+        StackObjectPool::stats(self)
+    }
+
+    #[cfg(feature = "leak-check")]
+    #[inline]
+    fn outstanding(&self) -> Vec<Outstanding> {
+        StackObjectPool::outstanding(self)
+    }
+}
+
+impl<T> WaitablePool<T> for StackObjectPool<T> {
+    #[inline]
+    fn waiters(&self) -> &WaitQueue {
+        &self.waiters
+    }
+
+    #[inline]
+    fn try_pull_owned_at(
+        self: &Arc<Self>,
+        location: &'static Location<'static>,
+    ) -> Result<Self::OwnedReusable, PoolError> {
+        StackObjectPool::try_pull_owned_at(self, location)
+    }
+}
+
+impl<T> TryFrom<PoolBuilder<T>> for StackObjectPool<T> {
+    type Error = PoolError;
+
+    #[inline]
+    fn try_from(builder: PoolBuilder<T>) -> Result<Self, Self::Error> {
+        let prewarm = builder.prewarm_count();
+        let capacity = builder.capacity();
+        let tracker = builder.leak_tracker();
+        let pool = Self {
+            nodes: NodeStack::new(),
+            reset: builder.reset,
+            validate: builder.validate,
+            init: builder.init,
+            health_check: builder.health_check,
+            health_check_retries: builder.health_check_retries,
+            capacity,
+            reset_mode: builder.reset_mode,
+            growth: builder.growth,
+            idle: AtomicUsize::new(0),
+            max_idle: builder.max_idle.unwrap_or(usize::MAX),
+            waiters: WaitQueue::new(),
+            stats: Stats::default(),
+            tracker,
+        };
+        pool.grow(prewarm)?;
+        Ok(pool)
+    }
+}
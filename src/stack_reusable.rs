@@ -0,0 +1,133 @@
+use crate::leak_check::Ticket;
+use crate::mapped_reusable::MappedReusable;
+use crate::node_stack::NodeId;
+use crate::stack_object_pool::StackObjectPool;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+
+/// Wrapper over T used by [`StackObjectPool`].
+///
+/// Access is allowed with [`std::ops::Deref`] or [`std::ops::DerefMut`]
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::StackObjectPool;
+///
+///  let pool = StackObjectPool::<u32>::new(
+///    ||  Default::default(),
+///    |v| {
+///      *v = 0;
+///    }
+///  );
+///  let mut item = pool.pull();
+///
+///  *item = 5;
+///  let work = *item * 5;
+/// ```
+pub struct StackReusable<'a, T> {
+    pool: &'a StackObjectPool<T>,
+    id: NodeId,
+    ticket: Ticket,
+}
+
+impl<'a, T> StackReusable<'a, T> {
+    /// Create new element
+    ///
+    /// # Arguments
+    /// * `pool` object pool owner
+    /// * `id` node that contains data
+    /// * `ticket`  leak check ticket of the pull
+    /// # Safety
+    /// * `id` has to be a node of `pool` holding an element used by nobody else
+    #[inline]
+    pub(crate) unsafe fn new(pool: &'a StackObjectPool<T>, id: NodeId, ticket: Ticket) -> Self {
+        Self { pool, id, ticket }
+    }
+
+    /// Take the element out of the pool. It will never return in the pool.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::StackObjectPool;
+    ///
+    ///  let pool = StackObjectPool::<u32>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      *v = 0;
+    ///    }
+    ///  );
+    ///  let mut item = pool.pull();
+    ///  *item = 5;
+    ///  let data: u32 = item.detach();
+    ///  assert_eq!(data, 5);
+    /// ```
+    #[inline]
+    pub fn detach(self) -> T {
+        let this = ManuallyDrop::new(self);
+        unsafe {
+            // SAFETY: there exists only this `StackReusable` with this id and it isn't dropped
+            this.pool.on_detach(this.id, &this.ticket)
+        }
+    }
+
+    /// Make a guard of a part of the element, like `std::cell::RefMut::map`.
+    ///
+    /// The whole element returns in the pool when the new guard is dropped. This
+    /// is an associated function to not shadow the functions of the element.
+    ///
+    /// # Example
+    /// ```rust
+    ///  use lockfree_object_pool::{StackObjectPool, StackReusable};
+    ///
+    ///  let pool = StackObjectPool::<(u32, String)>::new(
+    ///    ||  Default::default(),
+    ///    |v| {
+    ///      v.1.clear();
+    ///    }
+    ///  );
+    ///  let mut item = StackReusable::map(pool.pull(), |v| &mut v.1);
+    ///  item.push_str("hello");
+    ///  assert_eq!(item.len(), 5);
+    /// ```
+    #[inline]
+    pub fn map<U, F>(this: Self, f: F) -> MappedReusable<Self, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        MappedReusable::new(this, f)
+    }
+}
+
+impl<T> DerefMut for StackReusable<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe {
+            // SAFETY: there exists only this `StackReusable` with this id
+            self.pool.get_mut(self.id)
+        }
+    }
+}
+
+impl<T> Deref for StackReusable<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe {
+            // SAFETY: there exists only this `StackReusable` with this id
+            self.pool.get(self.id)
+        }
+    }
+}
+
+impl<T> Drop for StackReusable<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            // SAFETY: there exists only this `StackReusable` with this id
+            self.pool.recycle(self.id, &self.ticket);
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for StackReusable<'_, T> {} // SAFETY: sending the data is allowed if it's Send
+unsafe impl<T: Sync> Sync for StackReusable<'_, T> {} // SAFETY: sharing the guard only shares the data
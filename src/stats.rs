@@ -0,0 +1,135 @@
+#[cfg(feature = "stats")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Snapshot of the statistics of a pool, returned by the `stats` functions
+/// of the object pools.
+///
+/// Only available with the `stats` feature.
+///
+/// # Example
+/// ```rust
+///  use lockfree_object_pool::MutexObjectPool;
+///
+///  let pool = MutexObjectPool::<u32>::new(Default::default, |v| *v = 0);
+///  drop(pool.pull());
+///  let _item = pool.pull();
+///
+///  let stats = pool.stats();
+///  assert_eq!(stats.pulls, 2);
+///  assert_eq!(stats.hits, 1);
+///  assert_eq!(stats.in_use, 1);
+/// ```
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Number of elements handed out.
+    pub pulls: usize,
+    /// Number of pulls served by an idle element, without calling `init`.
+    pub hits: usize,
+    /// Number of calls of `init`.
+    pub inits: usize,
+    /// Number of elements returned in the pool.
+    pub returns: usize,
+    /// Number of elements currently handed out.
+    pub in_use: usize,
+    /// Number of idle elements currently in the pool.
+    pub idle: usize,
+    /// Number of pages of a [`crate::LinearObjectPool`], 0 for the other pools.
+    pub pages: usize,
+}
+
+/// Counters of a pool, updated with relaxed atomics.
+///
+/// Without the `stats` feature it has no field and all its functions are no-ops.
+#[derive(Default)]
+pub struct Stats {
+    #[cfg(feature = "stats")]
+    pulls: AtomicUsize,
+    #[cfg(feature = "stats")]
+    hits: AtomicUsize,
+    #[cfg(feature = "stats")]
+    inits: AtomicUsize,
+    #[cfg(feature = "stats")]
+    returns: AtomicUsize,
+    #[cfg(feature = "stats")]
+    in_use: AtomicUsize,
+}
+
+impl Stats {
+    /// An element is handed out, `hit` if it was idle.
+    #[inline]
+    pub fn on_pull(&self, hit: bool) {
+        #[cfg(feature = "stats")]
+        {
+            self.pulls.fetch_add(1, Ordering::Relaxed);
+            self.in_use.fetch_add(1, Ordering::Relaxed);
+            if hit {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        #[cfg(not(feature = "stats"))]
+        let _ = hit;
+    }
+
+    #[inline]
+    pub fn on_init(&self) {
+        #[cfg(feature = "stats")]
+        self.inits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn on_return(&self) {
+        #[cfg(feature = "stats")]
+        {
+            self.returns.fetch_add(1, Ordering::Relaxed);
+            self.in_use.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    pub fn on_detach(&self) {
+        #[cfg(feature = "stats")]
+        self.in_use.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn snapshot(&self, idle: usize, pages: usize) -> PoolStats {
+        PoolStats {
+            pulls: self.pulls.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            inits: self.inits.load(Ordering::Relaxed),
+            returns: self.returns.load(Ordering::Relaxed),
+            in_use: self.in_use.load(Ordering::Relaxed),
+            idle,
+            pages,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "stats"))]
+    #[test]
+    fn test_stats_disabled() {
+        assert_eq!(core::mem::size_of::<Stats>(), 0);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_stats_enabled() {
+        let stats = Stats::default();
+        stats.on_init();
+        stats.on_pull(false);
+        stats.on_pull(true);
+        stats.on_return();
+        stats.on_detach();
+        let snapshot = stats.snapshot(1, 0);
+        assert_eq!(snapshot.pulls, 2);
+        assert_eq!(snapshot.hits, 1);
+        assert_eq!(snapshot.in_use, 0);
+        assert_eq!(snapshot.idle, 1);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::linear_page::LinearPage;
+use crate::page::PageId;
+use crate::thread_index::{self, ExitHook, ThreadInfo};
+use alloc::sync::Arc;
+use core::cell::UnsafeCell;
+use core::hint;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Per thread caches of idle elements in front of the pages of a
+/// [`crate::LinearObjectPool`].
+///
+/// Each thread uses the magazine of its index, so a pull and a drop on the
+/// same thread usually don't touch the bitmaps shared by all the threads.
+/// When more threads run than there are magazines, a magazine is owned by the
+/// last thread which used it. A magazine is given back to the pages when it
+/// overflows or when its owner exits.
+pub struct ThreadCache<T, const N: usize> {
+    magazines: Box<[Magazine<T, N>]>,
+    size: usize,
+    hook: Arc<ExitHook>,
+}
+
+/// Idle elements of a thread, on its own cache line.
+#[repr(align(128))]
+struct Magazine<T, const N: usize> {
+    locked: AtomicBool,
+    owner: UnsafeCell<u64>,
+    slots: UnsafeCell<Vec<(*const LinearPage<T, N>, PageId)>>,
+}
+
+impl<T, const N: usize> Magazine<T, N> {
+    #[inline]
+    fn new(size: usize) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            owner: UnsafeCell::new(0),
+            slots: UnsafeCell::new(Vec::with_capacity(size)),
+        }
+    }
+
+    #[inline]
+    fn try_lock(&self) -> Option<MagazineGuard<'_, T, N>> {
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| MagazineGuard { magazine: self })
+    }
+
+    #[inline]
+    fn lock(&self) -> MagazineGuard<'_, T, N> {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+            hint::spin_loop();
+        }
+    }
+}
+
+struct MagazineGuard<'a, T, const N: usize> {
+    magazine: &'a Magazine<T, N>,
+}
+
+impl<T, const N: usize> MagazineGuard<'_, T, N> {
+    #[inline]
+    #[allow(clippy::mut_from_ref)]
+    fn slots(&self) -> &mut Vec<(*const LinearPage<T, N>, PageId)> {
+        unsafe {
+            // SAFETY: the magazine is locked
+            &mut *self.magazine.slots.get()
+        }
+    }
+
+    /// Make sure that the magazine is given back when the thread `id` exits,
+    /// instead of its previous owner.
+    #[inline]
+    fn adopt(&self, id: u64, hook: &Arc<ExitHook>) {
+        let owner = unsafe {
+            // SAFETY: the magazine is locked
+            &mut *self.magazine.owner.get()
+        };
+        if *owner != id {
+            *owner = id;
+            thread_index::on_exit(hook);
+        }
+    }
+
+    #[inline]
+    fn is_owned_by(&self, id: u64) -> bool {
+        unsafe {
+            // SAFETY: the magazine is locked
+            *self.magazine.owner.get() == id
+        }
+    }
+
+    /// Give the `count` oldest elements back to their pages.
+    #[inline]
+    fn flush(&self, count: usize) {
+        for (page, page_id) in self.slots().drain(..count) {
+            unsafe {
+                // SAFETY: the pages of the cache live as long as the pool
+                (*page).free(&page_id);
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for MagazineGuard<'_, T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        self.magazine.locked.store(false, Ordering::Release);
+    }
+}
+
+impl<T, const N: usize> ThreadCache<T, N> {
+    /// Create caches of `size` elements per thread, disabled if `size` is 0.
+    #[inline]
+    pub fn new(size: usize) -> Box<Self> {
+        let nb_magazines = if size == 0 {
+            0
+        } else {
+            thread_index::available_parallelism()
+        };
+        let cache = Box::new(Self {
+            magazines: (0..nb_magazines).map(|_| Magazine::new(size)).collect(),
+            size,
+            hook: Arc::default(),
+        });
+        let target: *const Self = &*cache;
+        unsafe {
+            // SAFETY: the cache is boxed and the hook is disabled when it's dropped
+            cache.hook.enable(target as *const (), Self::on_thread_exit);
+        }
+        cache
+    }
+
+    /// Take an idle element from the magazine of the current thread.
+    #[inline]
+    pub fn pop(&self) -> Option<(&LinearPage<T, N>, PageId)> {
+        let magazine = self.lock_current()?;
+        magazine.slots().pop().map(|(page, page_id)| {
+            (
+                unsafe {
+                    // SAFETY: the pages of the cache live as long as the pool
+                    &*page
+                },
+                page_id,
+            )
+        })
+    }
+
+    /// Keep an idle element in the magazine of the current thread, return false
+    /// if it has to be given back to its page.
+    #[inline]
+    pub fn push(&self, page: &LinearPage<T, N>, page_id: &PageId) -> bool {
+        let Some(magazine) = self.lock_current() else {
+            return false;
+        };
+        if magazine.slots().len() == self.size {
+            magazine.flush(self.size.div_ceil(2));
+        }
+        magazine.slots().push((page, *page_id));
+        true
+    }
+
+    /// Give all the elements back to their pages.
+    #[inline]
+    pub fn flush(&mut self) {
+        for magazine in self.magazines.iter() {
+            let magazine = magazine.lock();
+            magazine.flush(magazine.slots().len());
+        }
+    }
+
+    /// Number of idle elements in the magazines.
+    #[cfg(feature = "stats")]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.magazines
+            .iter()
+            .map(|magazine| magazine.lock().slots().len())
+            .sum()
+    }
+
+    #[inline]
+    fn lock_current(&self) -> Option<MagazineGuard<'_, T, N>> {
+        if self.magazines.is_empty() {
+            return None;
+        }
+        let thread = thread_index::current()?;
+        let magazine = self.magazines[thread.index % self.magazines.len()].try_lock()?;
+        magazine.adopt(thread.id, &self.hook);
+        Some(magazine)
+    }
+
+    /// Give the magazine of `thread` back to the pages, unless another thread
+    /// has used it since.
+    ///
+    /// # Safety
+    /// `cache` has to be a valid pointer to a `ThreadCache<T>`
+    unsafe fn on_thread_exit(cache: *const (), thread: ThreadInfo) {
+        let cache = &*(cache as *const Self);
+        let magazine = cache.magazines[thread.index % cache.magazines.len()].lock();
+        if magazine.is_owned_by(thread.id) {
+            magazine.flush(magazine.slots().len());
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for ThreadCache<T, N> {
+    #[inline]
+    fn drop(&mut self) {
+        // the exiting threads must not touch the pages once they're dropped
+        self.hook.disable();
+    }
+}
+
+unsafe impl<T: Send, const N: usize> Send for ThreadCache<T, N> {} // SAFETY: it only references the pages of its pool
+unsafe impl<T: Sync, const N: usize> Sync for ThreadCache<T, N> {} // SAFETY: the magazines are locked
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_cache_exit_of_previous_owner() {
+        let head = LinearPage::<u32, 8>::new();
+        let cache = ThreadCache::<u32, 8>::new(8);
+        let (page, page_id) = head.alloc(0, usize::MAX, 1);
+        assert!(cache.push(page, &page_id));
+        let current = thread_index::current().unwrap();
+        let target = &*cache as *const ThreadCache<u32, 8> as *const ();
+        let magazine = &cache.magazines[current.index % cache.magazines.len()];
+
+        // a thread which used the magazine before doesn't flush it
+        let previous = ThreadInfo {
+            index: current.index,
+            id: u64::MAX,
+        };
+        unsafe { ThreadCache::<u32, 8>::on_thread_exit(target, previous) };
+        assert_eq!(magazine.lock().slots().len(), 1);
+
+        unsafe { ThreadCache::<u32, 8>::on_thread_exit(target, current) };
+        assert_eq!(magazine.lock().slots().len(), 0);
+        assert_eq!(head.alloc(0, usize::MAX, 1).1, page_id);
+    }
+}
@@ -0,0 +1,171 @@
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+
+/// Indexes of the exited threads, reused by the next threads.
+static FREE_INDEXES: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+static NEXT_INDEX: AtomicUsize = AtomicUsize::new(0);
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD: ThreadState = ThreadState::new();
+}
+
+/// Identity of the current thread.
+#[derive(Clone, Copy)]
+pub struct ThreadInfo {
+    /// Small index, unique among the running threads.
+    pub index: usize,
+    /// Unique id, never reused, never 0.
+    pub id: u64,
+}
+
+/// Function called with its target and the identity of the exiting thread.
+type ExitFn = unsafe fn(*const (), ThreadInfo);
+
+/// Action run by a thread when it exits, with its identity.
+#[derive(Default)]
+pub struct ExitHook {
+    target: Mutex<Option<(*const (), ExitFn)>>,
+}
+
+impl ExitHook {
+    /// Call `f(target, thread)` when a registered thread exits.
+    ///
+    /// # Safety
+    /// `target` has to be valid for `f` until [`ExitHook::disable`] is called.
+    #[inline]
+    pub unsafe fn enable(&self, target: *const (), f: ExitFn) {
+        *self.target.lock().unwrap_or_else(|err| err.into_inner()) = Some((target, f));
+    }
+
+    /// Never run the hook again, it waits for a running one.
+    #[inline]
+    pub fn disable(&self) {
+        *self.target.lock().unwrap_or_else(|err| err.into_inner()) = None;
+    }
+
+    #[inline]
+    fn run(&self, thread: ThreadInfo) {
+        let target = self.target.lock().unwrap_or_else(|err| err.into_inner());
+        if let Some((target, f)) = *target {
+            unsafe {
+                // SAFETY: target is valid while the hook is enabled
+                f(target, thread)
+            }
+        }
+    }
+}
+
+unsafe impl Send for ExitHook {} // SAFETY: target is only used under the lock
+unsafe impl Sync for ExitHook {} // SAFETY: target is only used under the lock
+
+struct ThreadState {
+    info: ThreadInfo,
+    hooks: RefCell<Vec<Weak<ExitHook>>>,
+}
+
+impl ThreadState {
+    fn new() -> Self {
+        let index = FREE_INDEXES
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .pop()
+            .unwrap_or_else(|| NEXT_INDEX.fetch_add(1, Ordering::Relaxed));
+        Self {
+            info: ThreadInfo {
+                index,
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            },
+            hooks: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl Drop for ThreadState {
+    fn drop(&mut self) {
+        for hook in self.hooks.take() {
+            if let Some(hook) = hook.upgrade() {
+                hook.run(self.info);
+            }
+        }
+        FREE_INDEXES
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(self.info.index);
+    }
+}
+
+/// Identity of the current thread, none while its thread locals are destroyed.
+#[inline]
+pub fn current() -> Option<ThreadInfo> {
+    THREAD.try_with(|state| state.info).ok()
+}
+
+/// Number of CPUs rounded up to a power of two, used to size the per thread structures.
+#[inline]
+pub fn available_parallelism() -> usize {
+    thread::available_parallelism()
+        .map_or(4, |nb| nb.get())
+        .next_power_of_two()
+}
+
+/// Run `hook` when the current thread exits, if it's still alive.
+#[inline]
+pub fn on_exit(hook: &Arc<ExitHook>) {
+    let _ = THREAD.try_with(|state| {
+        let mut hooks = state.hooks.borrow_mut();
+        hooks.retain(|other| other.strong_count() > 0);
+        if !hooks
+            .iter()
+            .any(|other| other.as_ptr() == Arc::as_ptr(hook))
+        {
+            hooks.push(Arc::downgrade(hook));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_index() {
+        let main = current().unwrap();
+        let other = thread::spawn(current).join().unwrap().unwrap();
+        assert_ne!(main.index, other.index);
+        assert_ne!(main.id, other.id);
+
+        // the index of an exited thread is reused
+        let next = thread::spawn(current).join().unwrap().unwrap();
+        assert_ne!(main.index, next.index);
+        assert_ne!(other.id, next.id);
+    }
+
+    #[test]
+    fn test_exit_hook() {
+        use core::sync::atomic::AtomicUsize;
+
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        unsafe fn count(_: *const (), _: ThreadInfo) {
+            RUNS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let hook = Arc::new(ExitHook::default());
+        unsafe { hook.enable(core::ptr::null(), count) };
+        let thread_hook = hook.clone();
+        thread::spawn(move || {
+            on_exit(&thread_hook);
+            on_exit(&thread_hook);
+        })
+        .join()
+        .unwrap();
+        assert_eq!(RUNS.load(Ordering::Relaxed), 1);
+
+        hook.disable();
+        let thread_hook = hook.clone();
+        thread::spawn(move || on_exit(&thread_hook)).join().unwrap();
+        assert_eq!(RUNS.load(Ordering::Relaxed), 1);
+    }
+}
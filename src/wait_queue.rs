@@ -0,0 +1,225 @@
+use crate::spin_lock::SpinLock;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use core::sync::atomic::{self, AtomicBool, AtomicUsize, Ordering};
+use core::task::Waker;
+#[cfg(feature = "std")]
+use std::thread::{self, Thread};
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+enum Wake {
+    #[cfg(feature = "std")]
+    Thread(Thread),
+    Task(SpinLock<Waker>),
+}
+
+/// Thread or task waiting for an element in a [`WaitQueue`].
+pub struct Waiter {
+    wake: Wake,
+    notified: AtomicBool,
+}
+
+impl Waiter {
+    #[inline]
+    pub fn is_notified(&self) -> bool {
+        self.notified.load(Ordering::Acquire)
+    }
+
+    /// Park the current thread until it is notified or `deadline` is reached.
+    ///
+    /// Return false if the deadline is reached without notification.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn park(&self, deadline: Option<Instant>) -> bool {
+        while !self.is_notified() {
+            match deadline {
+                None => thread::park(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return false;
+                    }
+                    thread::park_timeout(deadline - now);
+                }
+            }
+        }
+        true
+    }
+
+    /// Check the notification, updating the waker used to wake up the task.
+    #[inline]
+    pub fn poll_notified(&self, waker: &Waker) -> bool {
+        if self.is_notified() {
+            return true;
+        }
+        #[allow(irrefutable_let_patterns)] // only tasks wait without std
+        if let Wake::Task(current) = &self.wake {
+            let mut current = current.lock();
+            if !current.will_wake(waker) {
+                *current = waker.clone();
+            }
+        }
+        self.is_notified()
+    }
+
+    #[inline]
+    fn wake(&self) {
+        self.notified.store(true, Ordering::Release);
+        match &self.wake {
+            #[cfg(feature = "std")]
+            Wake::Thread(thread) => thread.unpark(),
+            Wake::Task(waker) => waker.lock().wake_by_ref(),
+        }
+    }
+}
+
+/// FIFO of threads and tasks waiting for an element to be returned in a pool.
+///
+/// A returned element can't be missed by a waiter as long as either
+/// * [`WaitQueue::register_thread`] and [`WaitQueue::notify_one`] are called
+///   under the same lock as the one protecting the pool elements
+/// * or the waiter tries again to get an element after its registration.
+pub struct WaitQueue {
+    waiters: SpinLock<VecDeque<Arc<Waiter>>>,
+    len: AtomicUsize,
+}
+
+impl WaitQueue {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            waiters: SpinLock::new(VecDeque::new()),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Add the current thread at the end of the queue.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn register_thread(&self) -> Arc<Waiter> {
+        self.register(Wake::Thread(thread::current()))
+    }
+
+    /// Add a task at the end of the queue.
+    #[inline]
+    pub fn register_waker(&self, waker: &Waker) -> Arc<Waiter> {
+        self.register(Wake::Task(SpinLock::new(waker.clone())))
+    }
+
+    #[inline]
+    fn register(&self, wake: Wake) -> Arc<Waiter> {
+        let waiter = Arc::new(Waiter {
+            wake,
+            notified: AtomicBool::new(false),
+        });
+        self.waiters.lock().push_back(waiter.clone());
+        self.len.fetch_add(1, Ordering::Relaxed);
+        // pairs with the fence of notify_one
+        atomic::fence(Ordering::SeqCst);
+        waiter
+    }
+
+    /// Remove `waiter` from the queue, return false if it has already been notified.
+    #[inline]
+    pub fn cancel(&self, waiter: &Arc<Waiter>) -> bool {
+        let mut waiters = self.waiters.lock();
+        match waiters.iter().position(|item| Arc::ptr_eq(item, waiter)) {
+            Some(index) => {
+                waiters.remove(index);
+                self.len.fetch_sub(1, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove `waiter` from the queue, giving its notification to the next
+    /// waiter if it has already been notified.
+    #[inline]
+    pub fn cancel_or_forward(&self, waiter: &Arc<Waiter>) {
+        if !self.cancel(waiter) {
+            self.notify_one();
+        }
+    }
+
+    /// Wake up the oldest waiter, return false if there is none.
+    #[inline]
+    pub fn notify_one(&self) -> bool {
+        // pairs with the fence of register
+        atomic::fence(Ordering::SeqCst);
+        if self.len.load(Ordering::Relaxed) == 0 {
+            return false;
+        }
+        let waiter = self.waiters.lock().pop_front();
+        match waiter {
+            Some(waiter) => {
+                self.len.fetch_sub(1, Ordering::Relaxed);
+                waiter.wake();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Wake as TaskWake;
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+
+    struct Counter(AtomicUsize);
+
+    impl TaskWake for Counter {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_wait_queue_01() {
+        let queue = WaitQueue::new();
+        let waiter = queue.register_thread();
+        assert!(!waiter.park(Some(Instant::now() + Duration::from_millis(1))));
+        assert!(queue.cancel(&waiter));
+        assert!(!queue.cancel(&waiter));
+        assert!(!queue.notify_one());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_wait_queue_02() {
+        let queue = Arc::new(WaitQueue::new());
+        let waiter = queue.register_thread();
+        let notifier = {
+            let queue = queue.clone();
+            thread::spawn(move || queue.notify_one())
+        };
+        assert!(waiter.park(None));
+        assert!(!queue.cancel(&waiter));
+        assert!(notifier.join().unwrap());
+    }
+
+    #[test]
+    fn test_wait_queue_03() {
+        let queue = WaitQueue::new();
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+
+        let waiter1 = queue.register_waker(&waker);
+        let waiter2 = queue.register_waker(&waker);
+        assert!(!waiter1.poll_notified(&waker));
+
+        assert!(queue.notify_one());
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        assert!(waiter1.poll_notified(&waker));
+        assert!(!waiter2.poll_notified(&waker));
+
+        queue.cancel_or_forward(&waiter2);
+        assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+        assert!(!queue.notify_one());
+    }
+}
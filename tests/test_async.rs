@@ -0,0 +1,239 @@
+#![cfg(feature = "std")]
+
+use lockfree_object_pool::{
+    LinearObjectPool, MutexObjectPool, NoneObjectPool, PoolBuilder, PoolError, ShardedObjectPool,
+    SpinLockObjectPool, StackObjectPool,
+};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+struct CountWaker(AtomicUsize);
+
+impl Wake for CountWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+macro_rules! test_async_generic {
+    ($name_01:ident, $name_02:ident, $name_03:ident, $pool:ident) => {
+        #[test]
+        fn $name_01() {
+            let pool = Arc::new($pool::<u32>::new_bounded(Default::default, |v| *v = 0, 1));
+            let counter = Arc::new(CountWaker(AtomicUsize::new(0)));
+            let waker = Waker::from(counter.clone());
+            let mut cx = Context::from_waker(&waker);
+
+            let item = pool.pull_owned();
+            let mut future = pin!(pool.pull_async());
+            assert!(future.as_mut().poll(&mut cx).is_pending());
+            assert_eq!(counter.0.load(Ordering::Relaxed), 0);
+
+            drop(item);
+            assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+            assert!(future.as_mut().poll(&mut cx).is_ready());
+        }
+
+        #[test]
+        fn $name_02() {
+            let pool = Arc::new($pool::<u32>::new_bounded(Default::default, |v| *v = 0, 1));
+            let counter = Arc::new(CountWaker(AtomicUsize::new(0)));
+            let waker = Waker::from(counter.clone());
+            let mut cx = Context::from_waker(&waker);
+
+            let item = pool.pull();
+            let mut future1 = Box::pin(pool.pull_async());
+            let mut future2 = pin!(pool.pull_async());
+            assert!(future1.as_mut().poll(&mut cx).is_pending());
+            assert!(future2.as_mut().poll(&mut cx).is_pending());
+
+            // the notification received by the cancelled future goes to the next one
+            drop(item);
+            assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+            drop(future1);
+            assert_eq!(counter.0.load(Ordering::Relaxed), 2);
+            assert!(future2.as_mut().poll(&mut cx).is_ready());
+        }
+
+        #[test]
+        fn $name_03() {
+            let pool = Arc::new($pool::<u32>::new_bounded(Default::default, |v| *v = 0, 2));
+            let children: Vec<_> = (0..8)
+                .map(|_| {
+                    let pool = Arc::clone(&pool);
+                    thread::spawn(move || {
+                        for _ in 0..200 {
+                            let mut item = block_on(pool.pull_async());
+                            *item += 1;
+                        }
+                    })
+                })
+                .collect();
+
+            for child in children {
+                child.join().unwrap();
+            }
+            let _item1 = pool.try_pull().unwrap();
+            let _item2 = pool.try_pull().unwrap();
+            assert!(pool.try_pull().is_err());
+        }
+    };
+}
+
+test_async_generic!(
+    test_linear_async_01,
+    test_linear_async_02,
+    test_linear_async_03,
+    LinearObjectPool
+);
+test_async_generic!(
+    test_mutex_async_01,
+    test_mutex_async_02,
+    test_mutex_async_03,
+    MutexObjectPool
+);
+test_async_generic!(
+    test_spin_lock_async_01,
+    test_spin_lock_async_02,
+    test_spin_lock_async_03,
+    SpinLockObjectPool
+);
+test_async_generic!(
+    test_sharded_async_01,
+    test_sharded_async_02,
+    test_sharded_async_03,
+    ShardedObjectPool
+);
+test_async_generic!(
+    test_stack_async_01,
+    test_stack_async_02,
+    test_stack_async_03,
+    StackObjectPool
+);
+
+#[test]
+fn test_unbounded_async() {
+    let pool = Arc::new(LinearObjectPool::<u32>::new(Default::default, |v| *v = 0));
+    let _items: Vec<_> = (0..100).map(|_| block_on(pool.pull_async())).collect();
+}
+
+#[test]
+#[should_panic(expected = "failed to create an object")]
+fn test_init_error_async() {
+    let pool = Arc::new(MutexObjectPool::try_new(|| "x".parse::<u32>(), |v| *v = 0).unwrap());
+    block_on(pool.pull_async());
+}
+
+#[test]
+fn test_none_async() {
+    let pool = Arc::new(NoneObjectPool::<u32>::new_bounded(Default::default, 1));
+    let counter = Arc::new(CountWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let item = pool.pull_owned();
+    let mut future = pin!(pool.pull_async());
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+
+    drop(item);
+    assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+    let Poll::Ready(_item) = future.as_mut().poll(&mut cx) else {
+        panic!("the future isn't ready");
+    };
+    assert!(pool.try_pull().is_err());
+}
+
+#[test]
+fn test_sharded_async_other_shard() {
+    let pool: Arc<ShardedObjectPool<u32>> = Arc::new(
+        PoolBuilder::new(u32::default)
+            .max_objects(2)
+            .shards(2)
+            .build(),
+    );
+    let counter = Arc::new(CountWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let local = pool.pull_owned();
+    let other = pool.pull_owned();
+    let mut future = pin!(pool.pull_async());
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+
+    // an element returned in a shard which isn't the local one wakes up the waiter
+    drop(other);
+    assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+    assert!(future.as_mut().poll(&mut cx).is_ready());
+    drop(local);
+}
+
+#[test]
+fn test_init_error_try_async() {
+    let pool = Arc::new(MutexObjectPool::try_new(|| "x".parse::<u32>(), |v| *v = 0).unwrap());
+    assert!(matches!(
+        block_on(pool.try_pull_async()),
+        Err(PoolError::Init(_))
+    ));
+    let pool = Arc::new(NoneObjectPool::<u32>::try_new(|| "x".parse::<u32>()).unwrap());
+    assert!(matches!(
+        block_on(pool.try_pull_async()),
+        Err(PoolError::Init(_))
+    ));
+}
+
+#[test]
+fn test_unhealthy_try_async() {
+    let pool: Arc<LinearObjectPool<u32>> = Arc::new(
+        PoolBuilder::new(u32::default)
+            .max_objects(1)
+            .health_check(|_| false)
+            .build(),
+    );
+    assert!(matches!(
+        block_on(pool.try_pull_async()),
+        Err(PoolError::Unhealthy)
+    ));
+    // the failed pull doesn't keep the capacity of the pool
+    assert!(matches!(pool.try_pull(), Err(PoolError::Unhealthy)));
+}
+
+#[test]
+fn test_try_pull_async_waits() {
+    let pool = Arc::new(StackObjectPool::<u32>::new_bounded(
+        Default::default,
+        |v| *v = 0,
+        1,
+    ));
+    let item = pool.pull_owned();
+    let waiter = {
+        let pool = Arc::clone(&pool);
+        thread::spawn(move || block_on(pool.try_pull_async()).map(|item| *item))
+    };
+    thread::sleep(std::time::Duration::from_millis(10));
+    drop(item);
+    assert_eq!(waiter.join().unwrap().unwrap(), 0);
+}
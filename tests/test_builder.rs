@@ -0,0 +1,373 @@
+#![cfg(feature = "std")]
+
+use lockfree_object_pool::{
+    Growth, LinearObjectPool, MutexObjectPool, NoneObjectPool, ObjectPool, PoolBuilder, PoolError,
+    ResetMode, SpinLockObjectPool, StackObjectPool,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn make_builder(inits: &Arc<AtomicUsize>, resets: &Arc<AtomicUsize>) -> PoolBuilder<u32> {
+    let inits = inits.clone();
+    let resets = resets.clone();
+    PoolBuilder::new(move || {
+        inits.fetch_add(1, Ordering::Relaxed);
+        0
+    })
+    .reset(move |v| {
+        resets.fetch_add(1, Ordering::Relaxed);
+        *v = 0;
+    })
+}
+
+fn check_prewarm<P>(count: usize, expected_inits: usize)
+where
+    P: ObjectPool<u32> + TryFrom<PoolBuilder<u32>, Error = PoolError>,
+{
+    let inits = Arc::new(AtomicUsize::new(0));
+    let resets = Arc::new(AtomicUsize::new(0));
+    let pool: P = make_builder(&inits, &resets).prewarm(count).build();
+    assert_eq!(inits.load(Ordering::Relaxed), expected_inits);
+
+    let items: Vec<_> = (0..count).map(|_| pool.pull()).collect();
+    assert_eq!(inits.load(Ordering::Relaxed), expected_inits);
+    drop(items);
+}
+
+fn check_reset_on_pull<P>()
+where
+    P: ObjectPool<u32> + TryFrom<PoolBuilder<u32>, Error = PoolError>,
+{
+    let inits = Arc::new(AtomicUsize::new(0));
+    let resets = Arc::new(AtomicUsize::new(0));
+    let pool: P = make_builder(&inits, &resets)
+        .reset_mode(ResetMode::OnPull)
+        .prewarm(1)
+        .build();
+
+    let mut item = pool.pull();
+    *item = 5;
+    drop(item);
+    let resets_after_return = resets.load(Ordering::Relaxed);
+
+    let item = pool.pull();
+    assert_eq!(*item, 0);
+    assert_eq!(resets.load(Ordering::Relaxed), resets_after_return + 1);
+}
+
+#[test]
+fn test_builder_prewarm() {
+    check_prewarm::<MutexObjectPool<u32>>(10, 10);
+    check_prewarm::<SpinLockObjectPool<u32>>(10, 10);
+    check_prewarm::<StackObjectPool<u32>>(10, 10);
+    check_prewarm::<LinearObjectPool<u32>>(40, 40);
+}
+
+#[test]
+fn test_builder_prewarm_bounded() {
+    let inits = Arc::new(AtomicUsize::new(0));
+    let resets = Arc::new(AtomicUsize::new(0));
+    let pool: MutexObjectPool<u32> = make_builder(&inits, &resets)
+        .prewarm(10)
+        .max_objects(4)
+        .build();
+    assert_eq!(inits.load(Ordering::Relaxed), 4);
+    let _items: Vec<_> = (0..4).map(|_| pool.pull()).collect();
+    assert!(pool.try_pull().is_err());
+}
+
+#[test]
+fn test_builder_reset_on_pull() {
+    check_reset_on_pull::<MutexObjectPool<u32>>();
+    check_reset_on_pull::<SpinLockObjectPool<u32>>();
+    check_reset_on_pull::<StackObjectPool<u32>>();
+    check_reset_on_pull::<LinearObjectPool<u32>>();
+}
+
+#[test]
+fn test_builder_growth() {
+    let inits = Arc::new(AtomicUsize::new(0));
+    let resets = Arc::new(AtomicUsize::new(0));
+    let pool: SpinLockObjectPool<u32> = make_builder(&inits, &resets)
+        .growth(Growth::Batch(4))
+        .max_objects(6)
+        .build();
+
+    let mut items = vec![pool.pull()];
+    assert_eq!(inits.load(Ordering::Relaxed), 4);
+    items.extend((0..3).map(|_| pool.pull()));
+    assert_eq!(inits.load(Ordering::Relaxed), 4);
+    items.push(pool.pull());
+    assert_eq!(inits.load(Ordering::Relaxed), 6);
+    items.push(pool.pull());
+    assert!(pool.try_pull().is_err());
+}
+
+#[test]
+fn test_builder_none() {
+    let pool: NoneObjectPool<u32> = PoolBuilder::new(Default::default).max_objects(1).build();
+    let _item = pool.pull();
+    assert!(pool.try_pull().is_err());
+}
+
+#[test]
+fn test_builder_growth_linear() {
+    let inits = Arc::new(AtomicUsize::new(0));
+    let resets = Arc::new(AtomicUsize::new(0));
+    let pool: LinearObjectPool<u32> = make_builder(&inits, &resets)
+        .growth(Growth::Batch(64))
+        .build();
+
+    // the pages are chained by batch, their elements are created by their first pull
+    let items: Vec<_> = (0..33).map(|_| pool.pull()).collect();
+    assert_eq!(inits.load(Ordering::Relaxed), 33);
+    drop(items);
+    let _items: Vec<_> = (0..96).map(|_| pool.pull()).collect();
+    assert_eq!(inits.load(Ordering::Relaxed), 96);
+}
+
+#[test]
+fn test_builder_max_idle() {
+    let inits = Arc::new(AtomicUsize::new(0));
+    let resets = Arc::new(AtomicUsize::new(0));
+    let pool: MutexObjectPool<u32> = make_builder(&inits, &resets)
+        .max_idle(2)
+        .max_objects(4)
+        .build();
+
+    let items: Vec<_> = (0..4).map(|_| pool.pull()).collect();
+    assert!(pool.try_pull().is_err());
+    drop(items);
+    assert_eq!(inits.load(Ordering::Relaxed), 4);
+
+    // the dropped elements are released from the bound
+    let items: Vec<_> = (0..4).map(|_| pool.pull()).collect();
+    assert_eq!(inits.load(Ordering::Relaxed), 6);
+    drop(items);
+}
+
+fn check_trim<P>(trim: impl Fn(&mut P), expected_inits: usize)
+where
+    P: ObjectPool<u32> + TryFrom<PoolBuilder<u32>, Error = PoolError>,
+{
+    let inits = Arc::new(AtomicUsize::new(0));
+    let resets = Arc::new(AtomicUsize::new(0));
+    let mut pool: P = make_builder(&inits, &resets).prewarm(64).build();
+    assert_eq!(inits.load(Ordering::Relaxed), 64);
+
+    trim(&mut pool);
+    let items: Vec<_> = (0..64).map(|_| pool.pull()).collect();
+    assert_eq!(inits.load(Ordering::Relaxed), expected_inits);
+    drop(items);
+}
+
+#[test]
+fn test_builder_trim() {
+    check_trim::<MutexObjectPool<u32>>(|pool| pool.trim(), 128);
+    check_trim::<SpinLockObjectPool<u32>>(|pool| pool.shrink_to(10), 118);
+    check_trim::<StackObjectPool<u32>>(|pool| pool.shrink_to(10), 118);
+    // the linear pool keeps its first page
+    check_trim::<LinearObjectPool<u32>>(|pool| pool.trim(), 96);
+    check_trim::<LinearObjectPool<u32>>(|pool| pool.shrink_to(40), 64);
+}
+
+/// Builder whose `init` fails once `remaining` elements have been created.
+fn make_fallible_builder(remaining: usize) -> PoolBuilder<u32> {
+    let remaining = AtomicUsize::new(remaining);
+    PoolBuilder::try_new(move || {
+        remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| v.checked_sub(1))
+            .map(|_| 0)
+            .map_err(|_| "no more element")
+    })
+}
+
+fn check_fallible_init<P>(remaining: usize)
+where
+    P: ObjectPool<u32> + TryFrom<PoolBuilder<u32>, Error = PoolError>,
+{
+    let pool: P = make_fallible_builder(remaining).max_objects(64).build();
+    let items: Vec<_> = (0..remaining).map(|_| pool.pull()).collect();
+    match pool.try_pull() {
+        Err(PoolError::Init(err)) => assert_eq!(err.to_string(), "no more element"),
+        _ => panic!("init should fail"),
+    }
+    // the capacity of the failed element is released
+    drop(items);
+    let items: Vec<_> = (0..remaining).map(|_| pool.pull()).collect();
+    assert_eq!(items.len(), remaining);
+
+    let pool = make_fallible_builder(remaining)
+        .prewarm(64)
+        .try_build::<P>();
+    assert!(matches!(pool, Err(PoolError::Init(_))));
+}
+
+#[test]
+fn test_builder_fallible_init() {
+    check_fallible_init::<MutexObjectPool<u32>>(3);
+    check_fallible_init::<SpinLockObjectPool<u32>>(3);
+    check_fallible_init::<StackObjectPool<u32>>(3);
+    check_fallible_init::<LinearObjectPool<u32>>(3);
+}
+
+fn check_validate<P>()
+where
+    P: ObjectPool<u32> + TryFrom<PoolBuilder<u32>, Error = PoolError>,
+{
+    let inits = Arc::new(AtomicUsize::new(0));
+    let resets = Arc::new(AtomicUsize::new(0));
+    let pool: P = make_builder(&inits, &resets)
+        .validate(|v| *v != 13)
+        .max_objects(1)
+        .build();
+
+    let mut item = pool.pull();
+    *item = 5;
+    drop(item);
+    let inits_before = inits.load(Ordering::Relaxed);
+    let mut item = pool.pull();
+    assert_eq!(*item, 0);
+    *item = 13;
+    drop(item);
+
+    // the rejected element is replaced by a new one
+    let item = pool.pull();
+    assert_eq!(*item, 0);
+    assert_eq!(inits.load(Ordering::Relaxed), inits_before + 1);
+}
+
+#[test]
+fn test_builder_validate() {
+    check_validate::<MutexObjectPool<u32>>();
+    check_validate::<SpinLockObjectPool<u32>>();
+    check_validate::<StackObjectPool<u32>>();
+    check_validate::<LinearObjectPool<u32>>();
+}
+
+#[test]
+fn test_builder_validate_refill_slot() {
+    let fail = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let pool: LinearObjectPool<u32> = {
+        let fail = fail.clone();
+        PoolBuilder::try_new(move || {
+            if fail.load(Ordering::Relaxed) {
+                Err("init failure")
+            } else {
+                Ok(0)
+            }
+        })
+        .validate(|v| *v != 13)
+        .max_objects(2)
+        .build()
+    };
+
+    let mut item = pool.pull();
+    *item = 13;
+    fail.store(true, Ordering::Relaxed);
+    drop(item);
+    // the slot of the dropped element is filled again by its next pull
+    assert!(matches!(pool.try_pull(), Err(PoolError::Init(_))));
+    fail.store(false, Ordering::Relaxed);
+    let items = [pool.pull(), pool.pull()];
+    assert_eq!(items.map(|item| *item), [0, 0]);
+}
+
+fn check_health_check<P>()
+where
+    P: ObjectPool<u32> + TryFrom<PoolBuilder<u32>, Error = PoolError>,
+{
+    let inits = Arc::new(AtomicUsize::new(0));
+    let pool: P = {
+        let inits = inits.clone();
+        PoolBuilder::new(move || {
+            inits.fetch_add(1, Ordering::Relaxed);
+            0
+        })
+    }
+    .health_check(|v| *v != 13)
+    .health_check_retries(2)
+    .max_objects(1)
+    .build();
+
+    let mut item = pool.pull();
+    *item = 13;
+    drop(item);
+    let inits_before = inits.load(Ordering::Relaxed);
+    let item = pool.pull();
+    assert_eq!(*item, 0);
+    assert_eq!(inits.load(Ordering::Relaxed), inits_before + 1);
+    drop(item);
+
+    let pool: P = PoolBuilder::new(|| 13)
+        .health_check(|v| *v != 13)
+        .health_check_retries(2)
+        .max_objects(1)
+        .build();
+    assert!(matches!(pool.try_pull(), Err(PoolError::Unhealthy)));
+    // the capacity is released on failure
+    assert!(matches!(pool.try_pull(), Err(PoolError::Unhealthy)));
+
+    let pool: P = PoolBuilder::new(|| 0)
+        .health_check(|v| *v != 13)
+        .health_check_retries(0)
+        .build();
+    let mut item = pool.pull();
+    *item = 13;
+    drop(item);
+    assert!(matches!(pool.try_pull(), Err(PoolError::Unhealthy)));
+    // the unhealthy element is dropped, not pulled again
+    assert_eq!(*pool.pull(), 0);
+}
+
+#[test]
+fn test_builder_health_check() {
+    check_health_check::<MutexObjectPool<u32>>();
+    check_health_check::<SpinLockObjectPool<u32>>();
+    check_health_check::<StackObjectPool<u32>>();
+    check_health_check::<LinearObjectPool<u32>>();
+
+    let inits = Arc::new(AtomicUsize::new(0));
+    let resets = Arc::new(AtomicUsize::new(0));
+    let pool: NoneObjectPool<u32> = make_builder(&inits, &resets)
+        .health_check(|_| false)
+        .health_check_retries(2)
+        .build();
+    assert!(matches!(pool.try_pull(), Err(PoolError::Unhealthy)));
+    assert_eq!(inits.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn test_builder_thread_cache() {
+    for (size, expected) in [(0, 1), (8, 2)] {
+        let pool: LinearObjectPool<u32> = PoolBuilder::new(Default::default)
+            .thread_cache(size)
+            .build();
+        let mut a = pool.pull();
+        let mut b = pool.pull();
+        *a = 1;
+        *b = 2;
+        drop(a);
+        drop(b);
+        // the pages give the first free element, the cache the last returned one
+        assert_eq!(*pool.pull(), expected);
+    }
+}
+
+#[test]
+fn test_builder_page_width() {
+    let inits = Arc::new(AtomicUsize::new(0));
+    let resets = Arc::new(AtomicUsize::new(0));
+    let pool: LinearObjectPool<u32, 8> = make_builder(&inits, &resets).prewarm(9).build();
+    assert_eq!(inits.load(Ordering::Relaxed), 9);
+    let items: Vec<_> = (0..10).map(|_| pool.pull()).collect();
+    assert_eq!(inits.load(Ordering::Relaxed), 10);
+    drop(items);
+
+    let inits = Arc::new(AtomicUsize::new(0));
+    let pool: LinearObjectPool<u32, 64> = make_builder(&inits, &resets).max_objects(100).build();
+    let items: Vec<_> = (0..100).map(|_| pool.pull()).collect();
+    assert!(pool.try_pull().is_err());
+    assert_eq!(inits.load(Ordering::Relaxed), 100);
+    drop(items);
+}
@@ -71,3 +71,258 @@ macro_rules! test_recycle_generic_01 {
         }
     };
 }
+
+#[macro_export]
+macro_rules! test_detach_generic_01 {
+    ($name:ident, $expression:expr) => {
+        #[test]
+        fn $name() {
+            let pool = $expression;
+
+            let mut item1 = pool.pull();
+            *item1 = 5;
+            assert_eq!(item1.detach(), 5);
+
+            let item2 = pool.pull();
+            assert_eq!(*item2, 0);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_detach_generic_02 {
+    ($name:ident, $expression:expr) => {
+        #[test]
+        fn $name() {
+            use std::sync::Arc;
+
+            let pool = Arc::new($expression);
+
+            let mut item1 = pool.pull_owned();
+            *item1 = 5;
+            assert_eq!(item1.detach(), 5);
+            assert_eq!(Arc::strong_count(&pool), 1);
+
+            let item2 = pool.pull_owned();
+            assert_eq!(*item2, 0);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_attach_generic_01 {
+    ($name:ident, $expression:expr) => {
+        #[test]
+        fn $name() {
+            let pool = $expression;
+
+            pool.attach(5);
+
+            let item = pool.pull();
+            assert_eq!(*item, 5);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_bounded_generic_01 {
+    ($name:ident, $expression:expr) => {
+        #[test]
+        fn $name() {
+            use lockfree_object_pool::PoolError;
+
+            let pool = $expression;
+
+            let item1 = pool.pull();
+            let item2 = pool.try_pull().unwrap();
+            assert!(matches!(pool.try_pull(), Err(PoolError::Exhausted)));
+
+            drop(item1);
+            let item3 = pool.try_pull().unwrap();
+            assert!(pool.try_pull().is_err());
+
+            item2.detach();
+            let _item4 = pool.try_pull().unwrap();
+            assert!(pool.try_pull().is_err());
+            drop(item3);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_bounded_generic_02 {
+    ($name:ident, $expression:expr) => {
+        #[test]
+        fn $name() {
+            use std::sync::Arc;
+            use std::thread;
+
+            let pool = Arc::new($expression);
+
+            let children: Vec<_> = (0..5)
+                .map(|_| {
+                    let pool = Arc::clone(&pool);
+                    thread::spawn(move || {
+                        for _ in 0..1000 {
+                            if let Ok(mut item) = pool.try_pull_owned() {
+                                *item += 1;
+                            }
+                        }
+                    })
+                })
+                .collect();
+
+            for child in children {
+                child.join().unwrap();
+            }
+
+            let _item1 = pool.try_pull().unwrap();
+            let _item2 = pool.try_pull().unwrap();
+            assert!(pool.try_pull().is_err());
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_blocking_generic_01 {
+    ($name:ident, $expression:expr) => {
+        #[cfg(feature = "std")]
+        #[test]
+        fn $name() {
+            use lockfree_object_pool::PoolError;
+            use std::sync::Arc;
+            use std::thread;
+            use std::time::Duration;
+
+            let pool = Arc::new($expression);
+
+            let mut item = pool.pull_owned();
+            *item = 5;
+            let _item2 = pool.pull();
+            assert!(matches!(
+                pool.pull_timeout(Duration::from_millis(10)),
+                Err(PoolError::Timeout)
+            ));
+
+            let child = thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                drop(item);
+            });
+            let item = pool.pull_timeout(Duration::from_secs(10)).unwrap();
+            assert_eq!(*item, 0);
+            child.join().unwrap();
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_blocking_generic_02 {
+    ($name:ident, $expression:expr) => {
+        #[cfg(feature = "std")]
+        #[test]
+        fn $name() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+            use std::sync::Arc;
+            use std::thread;
+
+            let pool = Arc::new($expression);
+            let counter = Arc::new(AtomicUsize::new(0));
+
+            let children: Vec<_> = (0..8)
+                .map(|_| {
+                    let pool = Arc::clone(&pool);
+                    let counter = Arc::clone(&counter);
+                    thread::spawn(move || {
+                        for _ in 0..200 {
+                            let mut item = pool.pull_owned_blocking();
+                            *item += 1;
+                            counter.fetch_add(1, Ordering::Relaxed);
+                        }
+                    })
+                })
+                .collect();
+
+            for child in children {
+                child.join().unwrap();
+            }
+            assert_eq!(counter.load(Ordering::Relaxed), 8 * 200);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_map_generic_01 {
+    ($name:ident, $expression:expr, $guard:ident) => {
+        #[test]
+        fn $name() {
+            let pool = $expression;
+
+            let mut item = $guard::map(pool.pull(), |v| &mut v.0);
+            *item = 5;
+            // the projection follows the element when the guard is moved
+            let mut items = vec![item];
+            *items[0] += 1;
+            assert_eq!(*items[0], 6);
+            drop(items);
+
+            let item = pool.pull();
+            assert_eq!(item.0, 6);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_map_generic_02 {
+    ($name:ident, $expression:expr, $guard:ident) => {
+        #[test]
+        fn $name() {
+            use lockfree_object_pool::MappedReusable;
+            use std::sync::Arc;
+
+            let pool = Arc::new($expression);
+
+            let item = $guard::map(pool.pull_owned(), |v| &mut v.1);
+            let mut item = MappedReusable::map(item, |v| {
+                v.push(0);
+                &mut v[0]
+            });
+            *item = 7;
+            let item = Box::new(item);
+            assert_eq!(**item, 7);
+            drop(item);
+
+            let item = pool.pull_owned();
+            assert_eq!(item.1, vec![7]);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_map_generic_03 {
+    ($name:ident, $expression:expr, $guard:ident) => {
+        #[test]
+        fn $name() {
+            use lockfree_object_pool::MappedReusable;
+
+            let pool = $expression;
+
+            // the projection on the whole element follows it as well
+            let mut item = $guard::map(pool.pull(), |v| v);
+            item.0 = 3;
+            item.1.push(4);
+            let mut items = vec![item];
+            assert_eq!(*items[0], (3, vec![4]));
+
+            // a zero sized part of the element stays reachable after a move
+            let item = MappedReusable::map(items.pop().unwrap(), |v| {
+                <&mut [u32; 0]>::try_from(&mut std::slice::from_mut(&mut v.0)[..0]).unwrap()
+            });
+            let item = Box::new(item);
+            assert_eq!(**item, []);
+            drop(item);
+
+            let item = pool.pull();
+            assert_eq!((item.0, &item.1), (3, &vec![4]));
+        }
+    };
+}
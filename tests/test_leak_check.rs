@@ -0,0 +1,144 @@
+#![cfg(feature = "leak-check")]
+
+use lockfree_object_pool::{
+    LinearObjectPool, MutexObjectPool, NoneObjectPool, ObjectPool, PoolBuilder, ShardedObjectPool,
+    SpinLockObjectPool, StackObjectPool,
+};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+fn check_forgotten<P: ObjectPool<u32>>(pool: P) {
+    let pool = Arc::new(pool);
+    let item = pool.pull();
+    let owned = pool.pull_owned();
+    let kept = pool.try_pull().unwrap();
+    drop(item);
+    std::mem::forget(owned);
+
+    let outstanding = pool.outstanding();
+    assert_eq!(outstanding.len(), 2);
+    assert!(outstanding
+        .iter()
+        .all(|item| item.location.file() == file!()));
+    assert!(outstanding[0].location.line() < outstanding[1].location.line());
+
+    drop(kept);
+    assert_eq!(pool.outstanding().len(), 1);
+}
+
+#[test]
+fn test_leak_check_linear() {
+    check_forgotten(LinearObjectPool::<u32>::new(Default::default, |v| *v = 0));
+}
+
+#[test]
+fn test_leak_check_mutex() {
+    check_forgotten(MutexObjectPool::<u32>::new(Default::default, |v| *v = 0));
+}
+
+#[test]
+fn test_leak_check_spin_lock() {
+    check_forgotten(SpinLockObjectPool::<u32>::new(Default::default, |v| *v = 0));
+}
+
+#[test]
+fn test_leak_check_sharded() {
+    check_forgotten(ShardedObjectPool::<u32>::new(Default::default, |v| *v = 0));
+}
+
+#[test]
+fn test_leak_check_stack() {
+    check_forgotten(StackObjectPool::<u32>::new(Default::default, |v| *v = 0));
+}
+
+#[test]
+fn test_leak_check_none() {
+    check_forgotten(NoneObjectPool::<u32>::new(Default::default));
+}
+
+#[test]
+fn test_leak_check_detach() {
+    let pool = MutexObjectPool::<u32>::new(Default::default, |v| *v = 0);
+    let _data = pool.pull().detach();
+    assert!(pool.outstanding().is_empty());
+
+    let pool = LinearObjectPool::<u32>::new(Default::default, |v| *v = 0);
+    let _data = pool.pull().detach();
+    assert!(pool.outstanding().is_empty());
+}
+
+#[test]
+fn test_leak_check_shared() {
+    let pool = Arc::new(LinearObjectPool::<u32>::new(Default::default, |v| *v = 0));
+    let item = pool.pull_shared();
+    let clone = item.clone();
+    drop(item);
+    assert_eq!(pool.outstanding().len(), 1);
+    drop(clone);
+    assert!(pool.outstanding().is_empty());
+}
+
+#[test]
+fn test_leak_check_blocking() {
+    let pool = Arc::new(MutexObjectPool::<u32>::new_bounded(
+        Default::default,
+        |v| *v = 0,
+        1,
+    ));
+    std::mem::forget(pool.pull_owned_blocking());
+    let outstanding = pool.outstanding();
+    assert_eq!(outstanding.len(), 1);
+    assert_eq!(outstanding[0].location.file(), file!());
+}
+
+#[test]
+fn test_leak_check_async() {
+    let pool = Arc::new(SpinLockObjectPool::<u32>::new(Default::default, |v| *v = 0));
+    let mut future = pin!(pool.pull_async());
+    let mut cx = Context::from_waker(Waker::noop());
+    let Poll::Ready(item) = future.as_mut().poll(&mut cx) else {
+        panic!("pool is not exhausted");
+    };
+    let outstanding = pool.outstanding();
+    assert_eq!(outstanding.len(), 1);
+    assert_eq!(outstanding[0].location.file(), file!());
+    drop(item);
+    assert!(pool.outstanding().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "still checked out")]
+fn test_leak_check_assert_no_leak() {
+    let pool: LinearObjectPool<u32> = PoolBuilder::new(Default::default)
+        .assert_no_leak(true)
+        .build();
+    std::mem::forget(pool.pull());
+}
+
+#[test]
+fn test_leak_check_assert_no_leak_clean() {
+    let pool: MutexObjectPool<u32> = PoolBuilder::new(Default::default)
+        .assert_no_leak(true)
+        .build();
+    drop(pool.pull());
+}
+
+#[test]
+fn test_leak_check_sharded_order() {
+    let pool: Arc<ShardedObjectPool<u32>> = Arc::new(
+        PoolBuilder::new(u32::default)
+            .max_objects(2)
+            .shards(2)
+            .build(),
+    );
+    // the elements come from both shards, they are listed in the order of their pull
+    let first = pool.pull_owned();
+    let second = pool.pull_owned();
+    let outstanding = pool.outstanding();
+    assert_eq!(outstanding.len(), 2);
+    assert!(outstanding[0].location.line() < outstanding[1].location.line());
+    drop(first);
+    drop(second);
+}
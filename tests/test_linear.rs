@@ -1,4 +1,5 @@
-use lockfree_object_pool::LinearObjectPool;
+use lockfree_object_pool::{LinearObjectPool, LinearOwnedReusable, LinearReusable, PoolBuilder};
+use std::sync::Arc;
 
 #[macro_use]
 mod test_generic;
@@ -13,9 +14,25 @@ fn make_recycle_pool() -> LinearObjectPool<u32> {
     LinearObjectPool::<u32>::new(Default::default, |_v| {})
 }
 
+fn make_map_pool() -> LinearObjectPool<(u32, Vec<u32>)> {
+    LinearObjectPool::<(u32, Vec<u32>)>::new(Default::default, |_v| {})
+}
+
+fn make_bounded_pool() -> LinearObjectPool<u32> {
+    LinearObjectPool::<u32>::new_bounded(Default::default, |v| *v = 0, 2)
+}
+
 test_generic_01!(test_linear_01, make_pool());
 test_generic_02!(test_linear_02, make_pool());
 test_recycle_generic_01!(test_linear_recycle_01, make_recycle_pool());
+test_detach_generic_01!(test_linear_detach_01, make_pool());
+test_detach_generic_02!(test_linear_detach_02, make_pool());
+test_attach_generic_01!(test_linear_attach_01, make_recycle_pool());
+test_bounded_generic_01!(test_linear_bounded_01, make_bounded_pool());
+test_bounded_generic_02!(test_linear_bounded_02, make_bounded_pool());
+test_map_generic_01!(test_linear_map_01, make_map_pool(), LinearReusable);
+test_map_generic_02!(test_linear_map_02, make_map_pool(), LinearOwnedReusable);
+test_map_generic_03!(test_linear_map_03, make_map_pool(), LinearReusable);
 
 #[test]
 fn test_linear_03() {
@@ -69,3 +86,187 @@ fn test_linear_04() {
 
     assert_eq!(addrs.len(), 2);
 }
+
+#[test]
+fn test_linear_bounded_03() {
+    let pool = LinearObjectPool::<u32>::new_bounded(Default::default, |v| *v = 0, 40);
+
+    let mut items: Vec<_> = (0..40).map(|_| pool.pull()).collect();
+    assert!(pool.try_pull().is_err());
+
+    items.drain(..5);
+    for _ in 0..5 {
+        items.push(pool.try_pull().unwrap());
+    }
+    assert!(pool.try_pull().is_err());
+}
+
+#[test]
+fn test_linear_shared_01() {
+    let pool = Arc::new(LinearObjectPool::<u32>::new_bounded(
+        Default::default,
+        |v| *v = 0,
+        1,
+    ));
+
+    let mut item = pool.pull_owned();
+    *item = 5;
+    let item = item.share();
+    let clone = item.clone();
+    assert_eq!(*clone, 5);
+
+    drop(item);
+    assert!(pool.try_pull().is_err());
+    drop(clone);
+
+    let item = pool.try_pull_shared().unwrap();
+    assert_eq!(*item, 0);
+}
+
+#[test]
+fn test_linear_shared_02() {
+    use std::thread;
+
+    let pool = Arc::new(make_recycle_pool());
+    let item = pool.pull_shared();
+
+    let children: Vec<_> = (0..8)
+        .map(|_| {
+            let item = item.clone();
+            thread::spawn(move || {
+                let clones: Vec<_> = (0..100).map(|_| item.clone()).collect();
+                clones.iter().map(|v| **v).sum::<u32>()
+            })
+        })
+        .collect();
+    drop(item);
+    for child in children {
+        assert_eq!(child.join().unwrap(), 0);
+    }
+    assert_eq!(Arc::strong_count(&pool), 1);
+
+    let mut items: Vec<_> = (0..2).map(|_| pool.pull()).collect();
+    *items[0] = 1;
+    *items[1] = 1;
+}
+
+#[test]
+fn test_linear_thread_cache() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    let inits = Arc::new(AtomicUsize::new(0));
+    let counter = inits.clone();
+    let pool: Arc<LinearObjectPool<u32>> = Arc::new(
+        PoolBuilder::new(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+            0
+        })
+        .thread_cache(8)
+        .build(),
+    );
+    assert_eq!(inits.load(Ordering::Relaxed), 0);
+
+    let thread_pool = pool.clone();
+    thread::spawn(move || {
+        let items: Vec<_> = (0..32).map(|_| thread_pool.pull()).collect();
+        drop(items);
+        // the same elements are reused by the thread
+        let items: Vec<_> = (0..32).map(|_| thread_pool.pull()).collect();
+        drop(items);
+    })
+    .join()
+    .unwrap();
+
+    // the cache of the exited thread has been given back to the pool
+    let _items: Vec<_> = (0..32).map(|_| pool.pull()).collect();
+    assert_eq!(inits.load(Ordering::Relaxed), 32);
+}
+
+fn make_narrow_pool() -> LinearObjectPool<u32, 8> {
+    PoolBuilder::new(u32::default).reset(|v| *v = 0).build()
+}
+
+fn make_wide_pool() -> LinearObjectPool<u32, 64> {
+    PoolBuilder::new(u32::default).reset(|v| *v = 0).build()
+}
+
+test_generic_02!(test_linear_narrow_02, make_narrow_pool());
+test_generic_02!(test_linear_wide_02, make_wide_pool());
+
+#[test]
+fn test_linear_reuse_first_pages() {
+    let pool: LinearObjectPool<u32, 8> = PoolBuilder::new(u32::default).thread_cache(0).build();
+
+    let mut items: Vec<_> = (0..8 * 100).map(|_| pool.pull()).collect();
+    let addr = &*items[3] as *const u32;
+    drop(items.swap_remove(3));
+
+    // the freed element of the first page is found despite the full pages
+    let item = pool.pull();
+    assert_eq!(&*item as *const u32, addr);
+}
+
+#[test]
+fn test_linear_attach_keeps_idle() {
+    let pool: LinearObjectPool<u32, 8> = PoolBuilder::new(|| 100).thread_cache(0).build();
+
+    // the first page only holds idle elements
+    let items: Vec<_> = (0..8)
+        .map(|value| {
+            let mut item = pool.pull();
+            *item = value;
+            item
+        })
+        .collect();
+    drop(items);
+
+    // the donated element goes in a new page, no idle element is dropped
+    pool.attach(8);
+    let items: Vec<_> = (0..9).map(|_| pool.pull()).collect();
+    let mut values: Vec<_> = items.iter().map(|item| **item).collect();
+    values.sort();
+    assert_eq!(values, (0..9).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_linear_attach_bounded() {
+    let pool: LinearObjectPool<u32, 8> = PoolBuilder::new(|| 100).max_objects(2).build();
+
+    // the attached elements past max_objects are dropped
+    for value in 0..10 {
+        pool.attach(value);
+    }
+    let items = [pool.pull(), pool.pull()];
+    assert_eq!(items.each_ref().map(|item| **item), [0, 1]);
+    assert!(pool.try_pull().is_err());
+    drop(items);
+
+    // an element detached from the pool leaves a place for an attached one
+    pool.pull().detach();
+    pool.attach(5);
+    pool.attach(6);
+    let mut values: Vec<_> = [pool.pull(), pool.pull()]
+        .iter()
+        .map(|item| **item)
+        .collect();
+    values.sort();
+    assert_eq!(values, [1, 5]);
+}
+
+fn make_striped_pool() -> LinearObjectPool<u32> {
+    PoolBuilder::new(u32::default)
+        .reset(|v| *v = 0)
+        .striping(true)
+        .build()
+}
+
+test_generic_02!(test_linear_striped_02, make_striped_pool());
+test_bounded_generic_02!(
+    test_linear_striped_bounded_02,
+    PoolBuilder::new(u32::default)
+        .reset(|v| *v = 0)
+        .max_objects(2)
+        .striping(true)
+        .build::<LinearObjectPool<u32>>()
+);
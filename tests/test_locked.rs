@@ -0,0 +1,78 @@
+use lockfree_object_pool::{
+    LockedObjectPool, LockedOwnedReusable, LockedReusable, PoolBuilder, RawLock,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{RwLock, RwLockWriteGuard};
+
+#[macro_use]
+mod test_generic;
+
+static ACQUISITIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Lock provided by a user, counting its acquisitions.
+struct CountingLock<T> {
+    lock: RwLock<T>,
+}
+
+impl<T> RawLock<T> for CountingLock<T> {
+    type Guard<'a>
+        = RwLockWriteGuard<'a, T>
+    where
+        Self: 'a;
+
+    fn new(data: T) -> Self {
+        Self {
+            lock: RwLock::new(data),
+        }
+    }
+
+    fn lock(&self) -> Self::Guard<'_> {
+        ACQUISITIONS.fetch_add(1, Ordering::Relaxed);
+        self.lock.write().unwrap()
+    }
+}
+
+type CountingPool<T> = LockedObjectPool<T, CountingLock<Vec<T>>>;
+
+fn make_pool() -> CountingPool<u32> {
+    PoolBuilder::new(u32::default).reset(|v| *v = 0).build()
+}
+
+fn make_recycle_pool() -> CountingPool<u32> {
+    PoolBuilder::new(u32::default).build()
+}
+
+fn make_map_pool() -> CountingPool<(u32, Vec<u32>)> {
+    PoolBuilder::new(Default::default).build()
+}
+
+fn make_bounded_pool() -> CountingPool<u32> {
+    PoolBuilder::new(u32::default)
+        .reset(|v| *v = 0)
+        .max_objects(2)
+        .build()
+}
+
+test_generic_01!(test_locked_01, make_pool());
+test_generic_02!(test_locked_02, make_pool());
+test_recycle_generic_01!(test_locked_recycle_01, make_recycle_pool());
+test_detach_generic_01!(test_locked_detach_01, make_pool());
+test_detach_generic_02!(test_locked_detach_02, make_pool());
+test_attach_generic_01!(test_locked_attach_01, make_recycle_pool());
+test_bounded_generic_01!(test_locked_bounded_01, make_bounded_pool());
+test_bounded_generic_02!(test_locked_bounded_02, make_bounded_pool());
+test_blocking_generic_01!(test_locked_blocking_01, make_bounded_pool());
+test_blocking_generic_02!(test_locked_blocking_02, make_bounded_pool());
+test_map_generic_01!(test_locked_map_01, make_map_pool(), LockedReusable);
+test_map_generic_02!(test_locked_map_02, make_map_pool(), LockedOwnedReusable);
+test_map_generic_03!(test_locked_map_03, make_map_pool(), LockedReusable);
+
+#[test]
+fn test_locked_custom_lock() {
+    let pool = make_pool();
+    let before = ACQUISITIONS.load(Ordering::Relaxed);
+    drop(pool.pull());
+    // the idle elements are only reached through the lock of the user
+    assert!(ACQUISITIONS.load(Ordering::Relaxed) > before);
+    assert_eq!(*pool.pull(), 0);
+}
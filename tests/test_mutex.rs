@@ -1,4 +1,6 @@
-use lockfree_object_pool::MutexObjectPool;
+#![cfg(feature = "std")]
+
+use lockfree_object_pool::{MutexObjectPool, MutexOwnedReusable, MutexReusable};
 
 #[macro_use]
 mod test_generic;
@@ -13,6 +15,24 @@ fn make_recycle_pool() -> MutexObjectPool<u32> {
     MutexObjectPool::<u32>::new(Default::default, |_v| {})
 }
 
+fn make_map_pool() -> MutexObjectPool<(u32, Vec<u32>)> {
+    MutexObjectPool::<(u32, Vec<u32>)>::new(Default::default, |_v| {})
+}
+
+fn make_bounded_pool() -> MutexObjectPool<u32> {
+    MutexObjectPool::<u32>::new_bounded(Default::default, |v| *v = 0, 2)
+}
+
 test_generic_01!(test_mutex_01, make_pool());
 test_generic_02!(test_mutex_02, make_pool());
 test_recycle_generic_01!(test_mutex_recycle_01, make_recycle_pool());
+test_detach_generic_01!(test_mutex_detach_01, make_pool());
+test_detach_generic_02!(test_mutex_detach_02, make_pool());
+test_attach_generic_01!(test_mutex_attach_01, make_recycle_pool());
+test_bounded_generic_01!(test_mutex_bounded_01, make_bounded_pool());
+test_bounded_generic_02!(test_mutex_bounded_02, make_bounded_pool());
+test_blocking_generic_01!(test_mutex_blocking_01, make_bounded_pool());
+test_blocking_generic_02!(test_mutex_blocking_02, make_bounded_pool());
+test_map_generic_01!(test_mutex_map_01, make_map_pool(), MutexReusable);
+test_map_generic_02!(test_mutex_map_02, make_map_pool(), MutexOwnedReusable);
+test_map_generic_03!(test_mutex_map_03, make_map_pool(), MutexReusable);
@@ -1,4 +1,5 @@
-use lockfree_object_pool::NoneObjectPool;
+use lockfree_object_pool::{NoneObjectPool, NoneReusable, PoolBuilder, PoolError};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[test]
 fn test_none() {
@@ -9,3 +10,51 @@ fn test_none() {
         *_v += 1;
     }
 }
+
+#[test]
+fn test_none_detach() {
+    let pool = NoneObjectPool::<u32>::new(Default::default);
+    let mut v = pool.pull();
+    *v = 5;
+    assert_eq!(v.detach(), 5);
+}
+
+#[test]
+fn test_none_bounded() {
+    let pool = NoneObjectPool::<u32>::new_bounded(Default::default, 1);
+    let v = pool.pull();
+    assert!(pool.try_pull().is_err());
+    drop(v);
+    let v = pool.try_pull().unwrap();
+    v.detach();
+    assert!(pool.try_pull().is_ok());
+}
+
+#[test]
+fn test_none_fallible_init() {
+    let fail = AtomicBool::new(true);
+    let pool: NoneObjectPool<u32> = PoolBuilder::try_new(move || {
+        if fail.swap(false, Ordering::Relaxed) {
+            Err("init failure")
+        } else {
+            Ok(5)
+        }
+    })
+    .max_objects(1)
+    .build();
+    assert!(matches!(pool.try_pull(), Err(PoolError::Init(_))));
+    // the capacity of the failed element is released
+    assert_eq!(*pool.try_pull().unwrap(), 5);
+}
+
+#[test]
+fn test_none_map() {
+    let pool = NoneObjectPool::<(u32, u32)>::new_bounded(Default::default, 1);
+    let mut v = NoneReusable::map(pool.pull(), |v| &mut v.1);
+    *v = 5;
+    let v = Box::new(v);
+    assert_eq!(**v, 5);
+    assert!(pool.try_pull().is_err());
+    drop(v);
+    assert!(pool.try_pull().is_ok());
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+use lockfree_object_pool::{
+    LinearObjectPool, MutexObjectPool, NoneObjectPool, ObjectPool, PoolError, SpinLockObjectPool,
+};
+use std::sync::Arc;
+
+fn pull_twice<P: ObjectPool<u32>>(pool: &P) {
+    for _ in 0..2 {
+        let mut v = pool.pull();
+        assert_eq!(*v, 0);
+        *v += 1;
+    }
+}
+
+fn pull_owned_twice<P: ObjectPool<u32>>(pool: &Arc<P>) {
+    for _ in 0..2 {
+        let mut v = pool.pull_owned();
+        assert_eq!(*v, 0);
+        *v += 1;
+    }
+}
+
+#[test]
+fn test_object_pool_01() {
+    pull_twice(&LinearObjectPool::<u32>::new(Default::default, |v| *v = 0));
+    pull_twice(&MutexObjectPool::<u32>::new(Default::default, |v| *v = 0));
+    pull_twice(&SpinLockObjectPool::<u32>::new(Default::default, |v| {
+        *v = 0
+    }));
+    pull_twice(&NoneObjectPool::<u32>::new(Default::default));
+}
+
+#[test]
+fn test_object_pool_02() {
+    pull_owned_twice(&Arc::new(LinearObjectPool::<u32>::new(
+        Default::default,
+        |v| *v = 0,
+    )));
+    pull_owned_twice(&Arc::new(MutexObjectPool::<u32>::new(
+        Default::default,
+        |v| *v = 0,
+    )));
+    pull_owned_twice(&Arc::new(SpinLockObjectPool::<u32>::new(
+        Default::default,
+        |v| *v = 0,
+    )));
+    pull_owned_twice(&Arc::new(NoneObjectPool::<u32>::new(Default::default)));
+}
+
+/// Pool of another crate, which only implements the required functions.
+struct BoxPool;
+
+impl ObjectPool<u32> for BoxPool {
+    type Reusable<'a> = Box<u32>;
+    type OwnedReusable = Box<u32>;
+
+    fn pull(&self) -> Box<u32> {
+        Box::new(0)
+    }
+
+    fn pull_owned(self: &Arc<Self>) -> Box<u32> {
+        Box::new(0)
+    }
+
+    fn try_pull(&self) -> Result<Box<u32>, PoolError> {
+        Ok(Box::new(0))
+    }
+
+    fn try_pull_owned(self: &Arc<Self>) -> Result<Box<u32>, PoolError> {
+        Ok(Box::new(0))
+    }
+}
+
+#[test]
+fn test_object_pool_external() {
+    pull_twice(&BoxPool);
+    pull_owned_twice(&Arc::new(BoxPool));
+    #[cfg(feature = "stats")]
+    assert_eq!(BoxPool.stats(), Default::default());
+    #[cfg(feature = "leak-check")]
+    assert!(BoxPool.outstanding().is_empty());
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "std")]
+
+use lockfree_object_pool::{MutexObjectPool, PoolBuilder, ShardedObjectPool};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+#[macro_use]
+mod test_generic;
+
+fn make_pool() -> ShardedObjectPool<u32> {
+    ShardedObjectPool::<u32>::new(Default::default, |v| {
+        *v = 0;
+    })
+}
+
+fn make_recycle_pool() -> ShardedObjectPool<u32, MutexObjectPool<u32>> {
+    ShardedObjectPool::<u32, MutexObjectPool<u32>>::new(Default::default, |_v| {})
+}
+
+fn make_bounded_pool() -> ShardedObjectPool<u32> {
+    PoolBuilder::new(Default::default)
+        .reset(|v| *v = 0)
+        .max_objects(2)
+        .shards(4)
+        .build()
+}
+
+test_generic_01!(test_sharded_01, make_pool());
+test_generic_02!(test_sharded_02, make_pool());
+test_recycle_generic_01!(test_sharded_recycle_01, make_recycle_pool());
+test_detach_generic_01!(test_sharded_detach_01, make_pool());
+test_bounded_generic_01!(test_sharded_bounded_01, make_bounded_pool());
+test_bounded_generic_02!(test_sharded_bounded_02, make_bounded_pool());
+
+#[test]
+fn test_sharded_steal() {
+    let inits = Arc::new(AtomicUsize::new(0));
+    let counter = inits.clone();
+    let pool: Arc<ShardedObjectPool<u32>> = Arc::new(
+        PoolBuilder::new(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+            0
+        })
+        .shards(4)
+        .build(),
+    );
+    assert_eq!(pool.shards(), 4);
+
+    // each thread returns its element in its own shard
+    let children: Vec<_> = (0..4)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || drop(pool.pull_owned()))
+        })
+        .collect();
+    for child in children {
+        child.join().unwrap();
+    }
+    let created = inits.load(Ordering::Relaxed);
+
+    // the idle elements of the other shards are stolen before calling init
+    let items: Vec<_> = (0..created).map(|_| pool.pull()).collect();
+    assert_eq!(inits.load(Ordering::Relaxed), created);
+    drop(items);
+}
+
+#[test]
+fn test_sharded_split_limits() {
+    let inits = Arc::new(AtomicUsize::new(0));
+    let counter = inits.clone();
+    let pool: ShardedObjectPool<u32> = PoolBuilder::new(move || {
+        counter.fetch_add(1, Ordering::Relaxed);
+        0
+    })
+    .prewarm(5)
+    .max_objects(5)
+    .shards(3)
+    .build();
+    assert_eq!(inits.load(Ordering::Relaxed), 5);
+
+    let items: Vec<_> = (0..5).map(|_| pool.pull()).collect();
+    assert!(pool.try_pull().is_err());
+    assert_eq!(inits.load(Ordering::Relaxed), 5);
+    drop(items);
+}
+
+#[test]
+fn test_sharded_shards_clamped() {
+    let pool: ShardedObjectPool<u32> = PoolBuilder::new(u32::default)
+        .max_objects(2)
+        .shards(8)
+        .build();
+    // each shard can hold an element
+    assert_eq!(pool.shards(), 2);
+    let items: Vec<_> = (0..2).map(|_| pool.pull()).collect();
+    assert!(pool.try_pull().is_err());
+    drop(items);
+}
@@ -1,4 +1,12 @@
-use lockfree_object_pool::SpinLockObjectPool;
+#[cfg(feature = "std")]
+use lockfree_object_pool::ParkBackoff;
+use lockfree_object_pool::{
+    Backoff, ExponentialBackoff, PoolBuilder, SpinBackoff, SpinLock, SpinLockObjectPool,
+    SpinLockOwnedReusable, SpinLockReusable,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
 
 #[macro_use]
 mod test_generic;
@@ -13,6 +21,114 @@ fn make_recycle_pool() -> SpinLockObjectPool<u32> {
     SpinLockObjectPool::<u32>::new(Default::default, |_v| {})
 }
 
+fn make_map_pool() -> SpinLockObjectPool<(u32, Vec<u32>)> {
+    SpinLockObjectPool::<(u32, Vec<u32>)>::new(Default::default, |_v| {})
+}
+
+fn make_bounded_pool() -> SpinLockObjectPool<u32> {
+    SpinLockObjectPool::<u32>::new_bounded(Default::default, |v| *v = 0, 2)
+}
+
 test_generic_01!(test_spin_lock_01, make_pool());
 test_generic_02!(test_spin_lock_02, make_pool());
 test_recycle_generic_01!(test_spin_lock_recycle_01, make_recycle_pool());
+test_detach_generic_01!(test_spin_lock_detach_01, make_pool());
+test_detach_generic_02!(test_spin_lock_detach_02, make_pool());
+test_attach_generic_01!(test_spin_lock_attach_01, make_recycle_pool());
+test_bounded_generic_01!(test_spin_lock_bounded_01, make_bounded_pool());
+test_bounded_generic_02!(test_spin_lock_bounded_02, make_bounded_pool());
+test_blocking_generic_01!(test_spin_lock_blocking_01, make_bounded_pool());
+test_blocking_generic_02!(test_spin_lock_blocking_02, make_bounded_pool());
+test_map_generic_01!(test_spin_lock_map_01, make_map_pool(), SpinLockReusable);
+test_map_generic_02!(
+    test_spin_lock_map_02,
+    make_map_pool(),
+    SpinLockOwnedReusable
+);
+test_map_generic_03!(test_spin_lock_map_03, make_map_pool(), SpinLockReusable);
+
+fn make_backoff_pool<B: Backoff>() -> SpinLockObjectPool<u32, B> {
+    SpinLockObjectPool::<u32, B>::new(u32::default, |v| *v = 0)
+}
+
+test_generic_02!(test_spin_lock_spin_02, make_backoff_pool::<SpinBackoff>());
+test_generic_02!(
+    test_spin_lock_exponential_02,
+    make_backoff_pool::<ExponentialBackoff>()
+);
+#[cfg(feature = "std")]
+test_generic_02!(test_spin_lock_park_02, make_backoff_pool::<ParkBackoff>());
+#[cfg(feature = "std")]
+test_blocking_generic_02!(
+    test_spin_lock_park_blocking_02,
+    PoolBuilder::new(u32::default)
+        .max_objects(2)
+        .build::<SpinLockObjectPool<u32, ParkBackoff>>()
+);
+
+fn make_fair_pool() -> SpinLockObjectPool<u32> {
+    PoolBuilder::new(u32::default)
+        .reset(|v| *v = 0)
+        .fair_lock(true)
+        .build()
+}
+
+test_generic_02!(test_spin_lock_fair_02, make_fair_pool());
+
+/// Count the threads which have taken a ticket and wait for the lock.
+static WAITING: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Default)]
+struct Announce(bool);
+
+impl Backoff for Announce {
+    fn snooze(&mut self) {
+        if !self.0 {
+            self.0 = true;
+            WAITING.fetch_add(1, Ordering::SeqCst);
+        }
+        thread::yield_now();
+    }
+}
+
+#[test]
+fn test_spin_lock_fair_order() {
+    // the lock of a pool built with `fair_lock(true)`
+    let lock = Arc::new(SpinLock::<Vec<usize>, Announce>::new_fair(Vec::new()));
+    let guard = lock.lock();
+    let children: Vec<_> = (0..8)
+        .map(|index| {
+            let lock = lock.clone();
+            let child = thread::spawn(move || lock.lock().push(index));
+            // the thread waits with its ticket before the next one starts
+            while WAITING.load(Ordering::SeqCst) != index + 1 {
+                thread::yield_now();
+            }
+            child
+        })
+        .collect();
+    drop(guard);
+    for child in children {
+        child.join().unwrap();
+    }
+
+    // the threads got the lock in their arrival order, none has been overtaken
+    assert_eq!(*lock.lock(), (0..8).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_spin_lock_reusable_new() {
+    use lockfree_object_pool::PoolError;
+    use std::mem::ManuallyDrop;
+
+    let pool = make_bounded_pool();
+    // an element wrapped outside of the pool takes a place in the pool
+    let item = SpinLockReusable::new(&pool, ManuallyDrop::new(7));
+    let _item2 = pool.pull();
+    assert!(matches!(pool.try_pull(), Err(PoolError::Exhausted)));
+
+    // its place is given back when it's detached
+    assert_eq!(item.detach(), 7);
+    let _item3 = pool.try_pull().unwrap();
+    assert!(matches!(pool.try_pull(), Err(PoolError::Exhausted)));
+}
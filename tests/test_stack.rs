@@ -0,0 +1,91 @@
+use lockfree_object_pool::{StackObjectPool, StackOwnedReusable, StackReusable};
+use std::sync::Arc;
+
+#[macro_use]
+mod test_generic;
+
+fn make_pool() -> StackObjectPool<u32> {
+    StackObjectPool::<u32>::new(Default::default, |v| {
+        *v = 0;
+    })
+}
+
+fn make_recycle_pool() -> StackObjectPool<u32> {
+    StackObjectPool::<u32>::new(Default::default, |_v| {})
+}
+
+fn make_map_pool() -> StackObjectPool<(u32, Vec<u32>)> {
+    StackObjectPool::<(u32, Vec<u32>)>::new(Default::default, |_v| {})
+}
+
+fn make_bounded_pool() -> StackObjectPool<u32> {
+    StackObjectPool::<u32>::new_bounded(Default::default, |v| *v = 0, 2)
+}
+
+test_generic_01!(test_stack_01, make_pool());
+test_generic_02!(test_stack_02, make_pool());
+test_recycle_generic_01!(test_stack_recycle_01, make_recycle_pool());
+test_detach_generic_01!(test_stack_detach_01, make_pool());
+test_detach_generic_02!(test_stack_detach_02, make_pool());
+test_attach_generic_01!(test_stack_attach_01, make_recycle_pool());
+test_bounded_generic_01!(test_stack_bounded_01, make_bounded_pool());
+test_bounded_generic_02!(test_stack_bounded_02, make_bounded_pool());
+test_map_generic_01!(test_stack_map_01, make_map_pool(), StackReusable);
+test_map_generic_02!(test_stack_map_02, make_map_pool(), StackOwnedReusable);
+test_map_generic_03!(test_stack_map_03, make_map_pool(), StackReusable);
+
+#[test]
+fn test_stack_lifo() {
+    let pool = make_recycle_pool();
+    let mut items: Vec<_> = (0..100).map(|_| pool.pull()).collect();
+    for (i, item) in items.iter_mut().enumerate() {
+        **item = i as u32;
+    }
+    drop(items);
+
+    // the last returned element is the first reused
+    for i in (0..100).rev() {
+        let item = pool.pull();
+        assert_eq!(*item, i);
+        item.detach();
+    }
+}
+
+#[test]
+fn test_stack_detach_reuse_node() {
+    let pool = make_pool();
+    let mut item = pool.pull();
+    *item = 5;
+    let addr = &*item as *const u32;
+    assert_eq!(item.detach(), 5);
+
+    // the node of the detached element holds the next new one
+    let item = pool.pull();
+    assert_eq!(*item, 0);
+    assert_eq!(&*item as *const u32, addr);
+}
+
+#[test]
+fn test_stack_threads() {
+    let pool = Arc::new(StackObjectPool::<Vec<u32>>::new(Vec::new, |v| v.clear()));
+    let threads: Vec<_> = (0..8)
+        .map(|t| {
+            let pool = pool.clone();
+            std::thread::spawn(move || {
+                for i in 0..10_000 {
+                    let mut items: Vec<_> = (0..(i % 4)).map(|_| pool.pull_owned()).collect();
+                    for item in items.iter_mut() {
+                        assert!(item.is_empty());
+                        item.push(t);
+                    }
+                    for item in items.iter() {
+                        assert_eq!(**item, [t]);
+                    }
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
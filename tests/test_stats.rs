@@ -0,0 +1,129 @@
+#![cfg(feature = "stats")]
+
+use lockfree_object_pool::{
+    LinearObjectPool, MutexObjectPool, NoneObjectPool, ObjectPool, PoolBuilder, PoolStats,
+    ShardedObjectPool, SpinLockObjectPool, StackObjectPool,
+};
+
+#[test]
+fn test_stats_mutex() {
+    let pool = MutexObjectPool::<u32>::new(Default::default, |v| *v = 0);
+    assert_eq!(ObjectPool::stats(&pool), PoolStats::default());
+    let a = pool.pull();
+    let b = pool.pull();
+    drop(a);
+    let _c = pool.pull();
+    drop(b);
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            pulls: 3,
+            hits: 1,
+            inits: 2,
+            returns: 2,
+            in_use: 1,
+            idle: 1,
+            pages: 0,
+        }
+    );
+}
+
+#[test]
+fn test_stats_spin_lock() {
+    let pool = SpinLockObjectPool::<u32>::new(Default::default, |v| *v = 0);
+    let item = pool.pull();
+    let _data = item.detach();
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            pulls: 1,
+            inits: 1,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_stats_linear() {
+    let pool = LinearObjectPool::<u32>::new(Default::default, |v| *v = 0);
+    let items: Vec<_> = (0..33).map(|_| pool.pull()).collect();
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            pulls: 33,
+            hits: 0,
+            inits: 33,
+            returns: 0,
+            in_use: 33,
+            idle: 0,
+            pages: 2,
+        }
+    );
+    drop(items);
+    // only the created elements are idle
+    assert_eq!(pool.stats().idle, 33);
+    drop(pool.pull());
+    assert_eq!(pool.stats().hits, 1);
+}
+
+#[test]
+fn test_stats_none() {
+    let pool = NoneObjectPool::<u32>::new(Default::default);
+    let item = pool.pull();
+    drop(pool.pull());
+    item.detach();
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            pulls: 2,
+            inits: 2,
+            returns: 1,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_stats_sharded() {
+    let pool: ShardedObjectPool<u32> = PoolBuilder::new(Default::default)
+        .prewarm(4)
+        .shards(2)
+        .build();
+    let item = pool.pull();
+    drop(pool.pull());
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            pulls: 2,
+            hits: 2,
+            inits: 4,
+            returns: 1,
+            in_use: 1,
+            idle: 3,
+            pages: 0,
+        }
+    );
+    drop(item);
+}
+
+#[test]
+fn test_stats_stack() {
+    let pool = StackObjectPool::<u32>::new(Default::default, |v| *v = 0);
+    let a = pool.pull();
+    let b = pool.pull();
+    drop(a);
+    let _c = pool.pull();
+    drop(b);
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            pulls: 3,
+            hits: 1,
+            inits: 2,
+            returns: 2,
+            in_use: 1,
+            idle: 1,
+            pages: 0,
+        }
+    );
+}